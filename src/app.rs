@@ -5,21 +5,32 @@ use crossterm::event::{
 use futures::StreamExt;
 use ratatui::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 use tokio::sync::watch;
 
 use crate::cache::{load_cached_sessions, save_cached_sessions, CachedSessionData};
-use crate::config::{AppConfig, PreviewScrollMode};
+use crate::config::{AppConfig, BroadcastBusyMode, PreviewScrollMode};
 use crate::copy_mode;
+use crate::diff::{DiffView, LoadedDiff};
+use crate::filter_query::SessionFacts;
+use crate::git_status::GitStatus;
+use crate::grouping::GroupBy;
+use crate::history::{self, HistoryEntry};
+use crate::launch::{self, LaunchEntry, LaunchProfile};
 use crate::resize_pane;
 use crate::selection::{self, ContentPosition, PreviewSelection};
 use crate::session::{
-    auto_select_index, build_flat_visible_items, build_visible_items, group_sessions_by_name,
-    resolve_selected_index, Agent, AgentSession, PromptState, SessionStatus, VisibleItem,
+    auto_select_index, build_flat_visible_items, build_visible_items, group_key, group_sessions,
+    now_millis, output_fingerprint, resolve_selected_index, Agent, AgentSession, ManualOrder,
+    PromptState, SessionStatus, SortMode, VisibleItem,
 };
+use crate::slash_commands::{self, SlashCommand};
 use crate::state;
 use crate::tmux::TmuxClient;
 use crate::ui;
+use crate::views::SavedView;
+use crate::worktree;
 
 pub enum Focus {
     Sessions,
@@ -32,6 +43,9 @@ pub struct PreviewTarget {
     pub agent: Agent,
 }
 
+const INTERRUPT_TIMEOUT_MS: u64 = 10_000;
+const QUEUED_PROMPT_RETRY_MS: u64 = 10_000;
+
 pub struct AppState {
     pub should_quit: bool,
     pub config: AppConfig,
@@ -43,6 +57,9 @@ pub struct AppState {
     pub collapsed_hidden_groups: HashSet<String>,
     pub unread_pane_ids: HashSet<String>,
     pub prev_status_map: HashMap<String, SessionStatus>,
+    pub status_changed_at: HashMap<String, u64>,
+    pub last_output_at: HashMap<String, u64>,
+    pub stuck_pane_ids: HashSet<String>,
     pub display_name_map: HashMap<String, String>,
     pub prompt_states: HashMap<String, PromptState>,
     pub preview_content: String,
@@ -53,7 +70,10 @@ pub struct AppState {
     pub preview_pane_area: Rect,
     pub preview_selection: Option<PreviewSelection>,
     pub copy_mode: Option<copy_mode::CopyModeState>,
-    pub pending_confirm_target: Option<String>,
+    pub pending_confirm: Option<PendingConfirm>,
+    /// Panes interrupted from the dashboard, with a label and the time the
+    /// interrupt was sent, until they are seen idle again.
+    pub pending_interrupts: HashMap<String, (String, u64)>,
     pub show_help: bool,
     pub sessions_expanded: bool,
 
@@ -64,37 +84,325 @@ pub struct AppState {
     pub session_filter_active: bool,
     pub session_filter_query: String,
     pub session_filter_cursor: usize,
+    pub prompt_composer: Option<PromptComposer>,
+    pub prompt_queues: HashMap<String, Vec<String>>,
+    /// Panes a queued prompt was last sent to, with the send time, so the next
+    /// one waits until the agent has been seen busy or the send timed out.
+    pub queued_prompt_sent_at: HashMap<String, u64>,
+    /// Panes whose queue is held after an interrupt, so the agent isn't
+    /// restarted right away, until the user sends a prompt or edits the queue.
+    pub paused_queues: HashSet<String>,
+    pub queue_editor: Option<QueueEditor>,
+    pub marked_pane_ids: HashSet<String>,
+    /// Pane id of the session a visual range selection starts at; the range
+    /// ends at `selected_index`. Kept by pane so re-sorting can't shift it.
+    pub visual_anchor: Option<String>,
+    pub command_palette: Option<CommandPalette>,
+    pub launch_picker: Option<LaunchPicker>,
+    /// Views saved from the dashboard; config views are listed before them.
+    pub saved_views: Vec<SavedView>,
+    /// Pinned sessions, keyed by `session::pin_key`.
+    pub pinned_sessions: HashSet<String>,
+    pub pinned_groups: HashSet<String>,
+    /// Tags and notes the user attached to sessions, keyed by
+    /// `session::pin_key`.
+    pub session_tags: HashMap<String, Vec<String>>,
+    pub session_notes: HashMap<String, String>,
+    /// Names given to sessions with `N`, keyed like tags; shown instead of
+    /// the pane title.
+    pub session_names: HashMap<String, String>,
+    /// Names given to groups with `N`, keyed by group key. Aliases of tmux
+    /// sessions take the place of the formatter's output.
+    pub group_aliases: HashMap<String, String>,
+    /// Worktree path per pane id for windows opened on a new worktree; killing
+    /// the pane offers to remove it.
+    pub session_worktrees: HashMap<String, String>,
+    /// Launch profile last picked with `c`, by group key; listed first next
+    /// time.
+    pub last_launch_profiles: HashMap<String, String>,
+    pub manual_order: ManualOrder,
+    pub group_by: GroupBy,
+    /// Group key per pane id under `group_by`; empty when grouping by tmux
+    /// session.
+    pub group_keys: HashMap<String, String>,
+    /// Repository root per cwd, `None` outside a repository.
+    pub repo_roots: HashMap<String, Option<String>>,
+    /// Git branch and working-tree state by session cwd.
+    pub git_status: HashMap<String, GitStatus>,
+    /// Set while the preview shows the working-tree diff instead of the pane.
+    pub diff_view: Option<DiffView>,
+    /// Pane whose diff is being loaded, with the loading task.
+    pub diff_loading: Option<(String, tokio::task::AbortHandle)>,
+    /// Rows of the session list as last rendered, and the index of the
+    /// first visible item, for mapping mouse positions to items.
+    pub session_list_rows: Rect,
+    pub session_list_offset: usize,
+    /// Item being dragged with the mouse to reorder it.
+    pub drag_index: Option<usize>,
+    /// Tab selected with the number keys, as an index into `all_views()`.
+    pub active_view: Option<usize>,
+    pub line_input: Option<LineInput>,
+    /// Unread sessions per tab: "All" first, then one per view.
+    pub view_unread_counts: Vec<usize>,
     pub toast_message: Option<String>,
     pub toast_deadline: Option<std::time::Instant>,
     pub initial_focused_info: Option<(String, String)>,
     pub flat_view: bool,
+    pub sort_mode: SortMode,
     pub unread_order: HashMap<String, u64>,
     pub unread_counter: u64,
     pub hidden_pane_ids: HashSet<String>,
     pub hidden_groups: HashSet<String>,
+    pub exited_sessions: Vec<AgentSession>,
+    pub history: Vec<HistoryEntry>,
+    pub recent_section_collapsed: bool,
     pub hidden_section_collapsed: bool,
     pub group_hidden_collapsed: HashSet<String>,
     pub collapsed_subgroups: HashSet<String>,
     pub collapsed_hidden_subgroups: HashSet<String>,
 }
 
+impl AppState {
+    /// Views shown as tabs: config views first, then saved ones.
+    pub fn all_views(&self) -> Vec<SavedView> {
+        self.config
+            .views
+            .iter()
+            .chain(&self.saved_views)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Destructive or disruptive action waiting for confirmation in the dialog.
+pub enum PendingConfirm {
+    KillPane(String),
+    /// Bulk kill of `(pane_target, label)` pairs. Above the configured
+    /// threshold the user must type `kill` into `typed` first.
+    KillPanes {
+        targets: Vec<(String, String)>,
+        typed: String,
+    },
+    Interrupt {
+        pane_id: String,
+        pane_target: String,
+        agent: Agent,
+        label: String,
+    },
+    /// Clean worktrees left behind by killed sessions.
+    RemoveWorktrees(Vec<PathBuf>),
+}
+
+/// One-line input shown in place of the filter at the bottom of the session
+/// list.
+pub struct LineInput {
+    pub purpose: LineInputPurpose,
+    pub text: String,
+    pub cursor: usize,
+}
+
+pub enum LineInputPurpose {
+    /// Name for a view saved from the current filter; `editing` indexes the
+    /// saved view being renamed or updated.
+    SaveView {
+        editing: Option<usize>,
+    },
+    /// Space- or comma-separated tags for the session with this key.
+    Tags {
+        key: String,
+    },
+    Note {
+        key: String,
+    },
+    RenameSession {
+        key: String,
+    },
+    /// `tmux_session_name` is set when the group is a tmux session, which
+    /// `renameTmuxSession` renames along with the alias.
+    RenameGroup {
+        key: String,
+        tmux_session_name: Option<String>,
+    },
+    /// Branch for a new worktree of `repo_root`, opened as a window in
+    /// `tmux_session_name`.
+    Worktree {
+        repo_root: PathBuf,
+        tmux_session_name: String,
+    },
+}
+
+impl LineInput {
+    fn new(purpose: LineInputPurpose, text: String) -> Self {
+        LineInput {
+            purpose,
+            cursor: text.chars().count(),
+            text,
+        }
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        match self.purpose {
+            LineInputPurpose::SaveView { editing: Some(_) } => "Rename view (empty deletes): ",
+            LineInputPurpose::SaveView { editing: None } => "Save view as: ",
+            LineInputPurpose::Tags { .. } => "Tags: ",
+            LineInputPurpose::Note { .. } => "Note: ",
+            LineInputPurpose::RenameSession { .. } => "Rename session (empty resets): ",
+            LineInputPurpose::RenameGroup { .. } => "Rename group (empty resets): ",
+            LineInputPurpose::Worktree { .. } => "New worktree branch: ",
+        }
+    }
+}
+
+/// Single-line prompt being typed for one agent pane.
+pub struct PromptComposer {
+    pub pane_id: String,
+    pub pane_target: String,
+    pub label: String,
+    pub text: String,
+    pub cursor: usize,
+    /// Index of the queued prompt being edited; `queue.len()` appends a new one.
+    pub editing: Option<usize>,
+    /// Panes to broadcast to instead of the single pane above.
+    pub broadcast_pane_ids: Vec<String>,
+}
+
+/// Fuzzy picker over the slash commands available to one pane's agent.
+pub struct CommandPalette {
+    pub pane_id: String,
+    pub pane_target: String,
+    pub label: String,
+    pub commands: Vec<SlashCommand>,
+    pub query: String,
+    pub cursor: usize,
+    /// Position within the filtered list.
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    pub fn matches(&self) -> Vec<usize> {
+        slash_commands::filter(&self.commands, &self.query)
+    }
+}
+
+/// Picker over launch profiles and target directories for a new window.
+pub struct LaunchPicker {
+    pub group_key: String,
+    pub tmux_session_name: String,
+    /// Pane whose directory is used by entries without one.
+    pub cwd_target: String,
+    pub profiles: Vec<LaunchProfile>,
+    /// Command line of each profile, for display.
+    pub summaries: Vec<String>,
+    pub entries: Vec<LaunchEntry>,
+    pub query: String,
+    pub cursor: usize,
+    /// Position within the filtered list.
+    pub selected: usize,
+}
+
+impl LaunchPicker {
+    pub fn matches(&self) -> Vec<usize> {
+        let haystacks: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let profile = &self.profiles[entry.profile];
+                format!(
+                    "{} {} {}",
+                    profile.name,
+                    entry.cwd.as_deref().unwrap_or(""),
+                    self.summaries[entry.profile]
+                )
+            })
+            .collect();
+        slash_commands::fuzzy_rank(&haystacks, &self.query)
+    }
+}
+
+/// Overlay listing the prompts queued for one pane.
+pub struct QueueEditor {
+    pub pane_id: String,
+    pub pane_target: String,
+    pub label: String,
+    pub selected: usize,
+}
+
 pub enum Message {
-    SessionsUpdated(
-        Vec<AgentSession>,
-        HashMap<String, String>,
-        HashMap<String, PromptState>,
-    ),
+    SessionsUpdated {
+        sessions: Vec<AgentSession>,
+        display_names: HashMap<String, String>,
+        prompt_states: HashMap<String, PromptState>,
+        last_output_at: HashMap<String, u64>,
+        live_pane_ids: HashSet<String>,
+    },
     PreviewUpdated(String),
+    /// Git status by session cwd.
+    GitStatusChanged(HashMap<String, GitStatus>),
+    /// Diff requested with `Action::ShowDiff`, or why it couldn't be loaded.
+    DiffLoaded {
+        pane_id: String,
+        diff: Result<LoadedDiff, String>,
+    },
+    /// A queued prompt taken off the queue could not be sent.
+    QueuedPromptFailed {
+        pane_id: String,
+        text: String,
+    },
 }
 
 pub enum Action {
     SwitchToPane(String),
     OpenPopup(String),
+    /// Opens a window running `profile` in `cwd`, or in the directory of
+    /// the pane at `cwd_target` when `cwd` is unknown.
     CreateSession {
         tmux_session_name: String,
         cwd_target: String,
+        cwd: Option<String>,
+        profile: LaunchProfile,
+    },
+    /// Adds a worktree for `branch` and opens a window in it.
+    CreateWorktree {
+        repo_root: PathBuf,
+        branch: String,
+        tmux_session_name: String,
     },
+    RemoveWorktrees(Vec<PathBuf>),
     KillPane(String),
+    /// Loads the diff of the repository containing `cwd` into the preview.
+    ShowDiff {
+        pane_id: String,
+        cwd: String,
+    },
+    RenameTmuxSession {
+        from: String,
+        to: String,
+    },
+    KillPanes(Vec<String>),
+    SendPrompt {
+        pane_id: String,
+        pane_target: String,
+        text: String,
+    },
+    Broadcast {
+        pane_ids: Vec<String>,
+        text: String,
+    },
+    Interrupt {
+        pane_id: String,
+        pane_target: String,
+        agent: Agent,
+        label: String,
+    },
+    /// Suspends the TUI and edits a prompt in `$VISUAL`/`$EDITOR`; handled by
+    /// the run loop since it needs the terminal.
+    ComposeInEditor(PromptComposer),
+    ResumeSession(HistoryEntry),
+    RespawnAgent {
+        pane_target: String,
+        cwd: Option<String>,
+        agent: Agent,
+    },
     ForwardScrollDown {
         target: String,
         col: u16,
@@ -113,8 +421,8 @@ pub async fn run(
     exit_immediately: bool,
 ) -> Result<()> {
     let config = crate::config::load_config(exit_on_switch);
-    let formatter_path = config.session_name_formatter.clone();
     let loaded_state = state::load_state(config.shared_state);
+    let group_by = loaded_state.group_by.unwrap_or(config.group_by);
 
     let focused_pane_info = {
         let tmux = TmuxClient::new(&config);
@@ -133,6 +441,9 @@ pub async fn run(
         collapsed_hidden_groups: loaded_state.collapsed_hidden_groups,
         unread_pane_ids: loaded_state.unread_pane_ids,
         prev_status_map: loaded_state.prev_status_map,
+        status_changed_at: loaded_state.status_changed_at,
+        last_output_at: HashMap::new(),
+        stuck_pane_ids: HashSet::new(),
         display_name_map: HashMap::new(),
         prompt_states: HashMap::new(),
         preview_content: String::new(),
@@ -143,7 +454,8 @@ pub async fn run(
         preview_pane_area: Rect::default(),
         preview_selection: None,
         copy_mode: None,
-        pending_confirm_target: None,
+        pending_confirm: None,
+        pending_interrupts: HashMap::new(),
         show_help: false,
         sessions_expanded: false,
 
@@ -154,14 +466,49 @@ pub async fn run(
         session_filter_active: false,
         session_filter_query: String::new(),
         session_filter_cursor: 0,
+        prompt_composer: None,
+        prompt_queues: loaded_state.prompt_queues,
+        queued_prompt_sent_at: HashMap::new(),
+        paused_queues: HashSet::new(),
+        queue_editor: None,
+        marked_pane_ids: HashSet::new(),
+        visual_anchor: None,
+        command_palette: None,
+        launch_picker: None,
+        saved_views: loaded_state.saved_views,
+        pinned_sessions: loaded_state.pinned_sessions,
+        pinned_groups: loaded_state.pinned_groups,
+        session_tags: loaded_state.session_tags,
+        session_notes: loaded_state.session_notes,
+        session_names: loaded_state.session_names,
+        group_aliases: loaded_state.group_aliases,
+        session_worktrees: loaded_state.session_worktrees,
+        last_launch_profiles: loaded_state.last_launch_profiles,
+        manual_order: loaded_state.manual_order,
+        group_by,
+        group_keys: HashMap::new(),
+        repo_roots: HashMap::new(),
+        git_status: HashMap::new(),
+        diff_view: None,
+        diff_loading: None,
+        session_list_rows: Rect::default(),
+        session_list_offset: 0,
+        drag_index: None,
+        active_view: None,
+        line_input: None,
+        view_unread_counts: Vec::new(),
         toast_message: None,
         toast_deadline: None,
         initial_focused_info: focused_pane_info,
         flat_view: default_flat_view,
+        sort_mode: loaded_state.sort_mode,
         unread_order: loaded_state.unread_order,
         unread_counter: loaded_state.unread_counter,
         hidden_pane_ids: loaded_state.hidden_pane_ids,
         hidden_groups: loaded_state.hidden_groups,
+        exited_sessions: loaded_state.exited_sessions,
+        history: history::load_history(),
+        recent_section_collapsed: loaded_state.recent_section_collapsed,
         hidden_section_collapsed: loaded_state.hidden_section_collapsed,
         group_hidden_collapsed: loaded_state.group_hidden_collapsed,
         collapsed_subgroups: HashSet::new(),
@@ -171,6 +518,7 @@ pub async fn run(
     // Load cached sessions for instant first render
     if let Some(cached) = load_cached_sessions() {
         state.sessions = cached.sessions;
+        state.sessions.extend(state.exited_sessions.iter().cloned());
         state.display_name_map = cached.display_names;
         refresh_visible_items(&mut state);
         if let Some(info) = state.initial_focused_info.take() {
@@ -182,101 +530,110 @@ pub async fn run(
 
     let (target_tx, target_rx) = watch::channel(Option::<PreviewTarget>::None);
 
+    // Group aliases, so the poll task can use them in place of the formatter
+    let (alias_tx, alias_rx) = watch::channel(state.group_aliases.clone());
+
     // Session polling task (every 2s)
     let poll_tx = tx.clone();
     tokio::spawn(async move {
         let config = crate::config::load_config(false);
         let tmux = TmuxClient::new(&config);
-        let mut formatter_cache: HashMap<String, String> = HashMap::new();
+        let mut formatter = config.session_name_formatter.clone().map(|command| {
+            crate::formatter::Formatter::new(
+                command,
+                config.session_name_formatter_mode,
+                std::time::Duration::from_millis(config.session_name_formatter_timeout_ms),
+            )
+        });
+        // pane_id -> (output fingerprint, when it last changed)
+        let mut output_seen: HashMap<String, (u64, u64)> = HashMap::new();
         loop {
-            if let Ok(mut sessions) = tmux.discover_sessions().await {
-                // Merge per-pane enrichment files written by agent plugins.
-                // Missing or malformed files are silently ignored — base detection
-                // remains the fallback.
-                for session in sessions.iter_mut() {
-                    if let Some(enrichment) = crate::enrichment::read(&session.pane_id) {
-                        if enrichment.agent == session.agent {
-                            if let Some(status) = enrichment.status_as_session_status() {
-                                session.status = status;
-                            }
-                            if enrichment.session_id.is_some() {
-                                session.session_id = enrichment.session_id;
-                            }
-                            if let Some(title) = enrichment.title {
-                                session.title = title;
-                            }
-                            if enrichment.cwd.is_some() {
-                                session.cwd = enrichment.cwd;
-                            }
-                            if enrichment.model.is_some() {
-                                session.model = enrichment.model;
-                            }
-                            if enrichment.agent_role.is_some() {
-                                session.agent_role = enrichment.agent_role;
-                            }
-                        }
-                    }
-                }
+            if let Ok(discovered) = tmux.discover_sessions().await {
+                let mut sessions = discovered.sessions;
+                crate::enrichment::merge_into(&mut sessions);
 
                 // TODO: opportunistic cleanup of orphaned enrichment files (pane no longer
                 // exists) — plugins are expected to delete on exit, so this is best-effort.
                 // See plan Phase B step 3.
 
-                let unique_names: Vec<String> = sessions
+                // Aliased names skip the template and formatter
+                let mut display_names = alias_rx.borrow().clone();
+                display_names
+                    .retain(|name, _| sessions.iter().any(|s| s.tmux_session_name == *name));
+                let unaliased: Vec<AgentSession> = sessions
                     .iter()
-                    .map(|s| s.tmux_session_name.clone())
-                    .collect::<std::collections::HashSet<_>>()
-                    .into_iter()
+                    .filter(|s| !display_names.contains_key(&s.tmux_session_name))
+                    .cloned()
                     .collect();
-
-                let mut display_names = HashMap::new();
-                for name in &unique_names {
-                    let formatted = if let Some(ref parts) = formatter_path {
-                        if let Some(cached) = formatter_cache.get(name) {
-                            cached.clone()
-                        } else {
-                            let (cmd, pre_args) = parts.split_first().unwrap();
-                            match tokio::process::Command::new(cmd)
-                                .args(pre_args)
-                                .arg(name)
-                                .output()
-                                .await
-                            {
-                                Ok(output) if output.status.success() => {
-                                    let result =
-                                        String::from_utf8_lossy(&output.stdout).trim().to_string();
-                                    formatter_cache.insert(name.clone(), result.clone());
-                                    result
-                                }
-                                _ => name.clone(),
-                            }
+                match (&config.session_name_template, formatter.as_mut()) {
+                    (Some(template), _) => {
+                        for lead in crate::session::tmux_session_leads(&unaliased) {
+                            let values = crate::template::TemplateValues::from_session(lead);
+                            let name = template.render(&values);
+                            let name = if name.is_empty() {
+                                lead.tmux_session_name.clone()
+                            } else {
+                                name
+                            };
+                            display_names.insert(lead.tmux_session_name.clone(), name);
                         }
-                    } else {
-                        name.clone()
-                    };
-                    display_names.insert(name.clone(), formatted);
+                    }
+                    (None, Some(formatter)) => {
+                        let contexts = crate::formatter::session_contexts(&unaliased);
+                        display_names.extend(formatter.format_all(&contexts).await);
+                    }
+                    (None, None) => {
+                        for session in &unaliased {
+                            display_names
+                                .entry(session.tmux_session_name.clone())
+                                .or_insert_with(|| session.tmux_session_name.clone());
+                        }
+                    }
                 }
 
-                // Detect prompt states for idle Claude sessions (opencode returns None)
+                // Capture visible content: prompt states for idle Claude sessions
+                // (opencode returns None), output fingerprints for stuck detection
                 let mut prompt_set = tokio::task::JoinSet::new();
-                for session in sessions.iter().filter(|s| s.status == SessionStatus::Idle) {
+                for session in sessions.iter() {
                     let target = session.pane_target.clone();
                     let pane_id = session.pane_id.clone();
                     let agent = session.agent;
+                    let is_idle = session.status == SessionStatus::Idle;
                     prompt_set.spawn(async move {
-                        let state = match crate::tmux::capture_pane_visible(&target).await {
-                            Ok(text) => crate::session::detect_prompt_state(agent, &text),
-                            Err(_) => crate::session::PromptState::None,
+                        let text = crate::tmux::capture_pane_visible(&target).await.ok();
+                        let state = match (&text, is_idle) {
+                            (Some(text), true) => {
+                                Some(crate::session::detect_prompt_state(agent, text))
+                            }
+                            (None, true) => Some(crate::session::PromptState::None),
+                            (_, false) => None,
                         };
-                        (pane_id, state)
+                        let fingerprint = text.as_deref().map(output_fingerprint);
+                        (pane_id, state, fingerprint)
                     });
                 }
                 let mut prompt_states = HashMap::new();
+                let now = now_millis();
                 while let Some(result) = prompt_set.join_next().await {
-                    if let Ok((pane_id, state)) = result {
-                        prompt_states.insert(pane_id, state);
+                    if let Ok((pane_id, state, fingerprint)) = result {
+                        if let Some(fingerprint) = fingerprint {
+                            let seen = output_seen
+                                .entry(pane_id.clone())
+                                .or_insert((fingerprint, now));
+                            if seen.0 != fingerprint {
+                                *seen = (fingerprint, now);
+                            }
+                        }
+                        if let Some(state) = state {
+                            prompt_states.insert(pane_id, state);
+                        }
                     }
                 }
+                output_seen.retain(|id, _| sessions.iter().any(|s| s.pane_id == *id));
+                let last_output_at: HashMap<String, u64> = output_seen
+                    .iter()
+                    .map(|(id, (_, at))| (id.clone(), *at))
+                    .collect();
 
                 // Save to cache
                 let cached_data = CachedSessionData {
@@ -285,11 +642,13 @@ pub async fn run(
                 };
                 save_cached_sessions(&cached_data);
 
-                let _ = poll_tx.send(Message::SessionsUpdated(
+                let _ = poll_tx.send(Message::SessionsUpdated {
                     sessions,
                     display_names,
                     prompt_states,
-                ));
+                    last_output_at,
+                    live_pane_ids: discovered.pane_ids,
+                });
             }
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
         }
@@ -300,6 +659,12 @@ pub async fn run(
     let fifo_path = pipe_watcher.fifo_path().to_string();
     crate::pipe_pane::spawn_preview_task(tx.clone(), target_rx, fifo_path);
 
+    // Git status task, fed the cwds of the current sessions
+    let (cwds_tx, cwds_rx) = watch::channel(Vec::<String>::new());
+    if state.config.git_status {
+        crate::git_status::spawn_status_task(tx.clone(), cwds_rx);
+    }
+
     let (resize_tx, resize_rx) = watch::channel::<Option<resize_pane::ResizeRequest>>(None);
     let resize_handle = resize_pane::spawn_resize_task(resize_rx);
 
@@ -317,27 +682,73 @@ pub async fn run(
             Some(Ok(event)) = event_stream.next() => {
                 match event {
                     Event::Key(key) => {
-                        let action = handle_key_event(&mut state, key, &target_tx);
+                        let mut action = handle_key_event(&mut state, key, &target_tx);
+                        if let Some(Action::ComposeInEditor(mut composer)) = action {
+                            // Replace the stream first so its reader thread stops
+                            // before the editor takes over the terminal
+                            event_stream = EventStream::new();
+                            action = match edit_in_external_editor(terminal, &composer.text) {
+                                Ok(Some(text)) => {
+                                    composer.text = text;
+                                    submit_prompt(&mut state, composer)
+                                }
+                                Ok(None) => {
+                                    show_toast(&mut state, "Empty prompt, not sent".to_string());
+                                    None
+                                }
+                                Err(_) => {
+                                    show_toast(&mut state, "Editor failed".to_string());
+                                    None
+                                }
+                            };
+                        }
                         if let Some(action) = action {
-                            process_action(&mut state, action, &target_tx).await;
+                            process_action(&mut state, action, &target_tx, &tx).await;
+                        }
+                        if let Some(action) = diff_follow_selection(&mut state, &target_tx) {
+                            process_action(&mut state, action, &target_tx, &tx).await;
                         }
                     }
                     Event::Mouse(mouse) => {
-                        if let Some(action) = handle_mouse_event(&mut state, mouse) {
-                            process_action(&mut state, action, &target_tx).await;
+                        if let Some(action) = handle_mouse_event(&mut state, mouse, &target_tx) {
+                            process_action(&mut state, action, &target_tx, &tx).await;
+                        }
+                        if let Some(action) = diff_follow_selection(&mut state, &target_tx) {
+                            process_action(&mut state, action, &target_tx, &tx).await;
                         }
                     }
                     _ => {}
                 }
             }
             Some(msg) = rx.recv() => {
-                handle_message(&mut state, msg, &target_tx);
+                handle_message(&mut state, msg, &target_tx, &tx);
             }
         }
 
         terminal.draw(|frame| ui::render(frame, &mut state))?;
 
         let _ = resize_tx.send(build_resize_request(&state));
+        cwds_tx.send_if_modified(|cwds| {
+            let mut current: Vec<String> = state
+                .sessions
+                .iter()
+                .filter_map(|s| s.cwd.clone())
+                .collect();
+            current.sort();
+            current.dedup();
+            let changed = *cwds != current;
+            if changed {
+                *cwds = current;
+            }
+            changed
+        });
+        alias_tx.send_if_modified(|aliases| {
+            let changed = *aliases != state.group_aliases;
+            if changed {
+                aliases.clone_from(&state.group_aliases);
+            }
+            changed
+        });
 
         // Check toast expiry
         if let Some(deadline) = state.toast_deadline {
@@ -365,6 +776,7 @@ async fn process_action(
     state: &mut AppState,
     action: Action,
     selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+    tx: &mpsc::UnboundedSender<Message>,
 ) {
     match action {
         Action::SwitchToPane(target) => {
@@ -380,43 +792,305 @@ async fn process_action(
         Action::CreateSession {
             tmux_session_name,
             cwd_target,
+            cwd,
+            profile,
         } => {
             let config = crate::config::load_config(state.config.exit_on_switch);
             let tmux = TmuxClient::new(&config);
-            if let Ok(cwd) = tmux.get_pane_cwd(&cwd_target).await {
-                if let Ok(Some(pane_info)) =
-                    tmux.create_window(&tmux_session_name, Some(&cwd)).await
-                {
+            let cwd = match cwd {
+                Some(cwd) => cwd,
+                None => match tmux.get_pane_cwd(&cwd_target).await {
+                    Ok(cwd) => cwd,
+                    Err(_) => return,
+                },
+            };
+            let command = profile.shell_command(&config);
+            let Ok(Some(pane_info)) = tmux
+                .create_window_with_command(&tmux_session_name, Some(&cwd), &command)
+                .await
+            else {
+                show_toast(state, format!("Could not start {}", profile.name));
+                return;
+            };
+            if let Some(ref window_name) = profile.window_name {
+                let window_target = pane_info
+                    .pane_target
+                    .rsplit_once('.')
+                    .map(|(window, _)| window)
+                    .unwrap_or(&pane_info.pane_target);
+                let _ = tmux.rename_window(window_target, window_name).await;
+            }
+            let _ = tmux.switch_to_pane(&pane_info.pane_target).await;
+            let agent = profile.agent(&config);
+            track_new_window(state, agent, pane_info, None, selected_pane_target);
+        }
+        Action::CreateWorktree {
+            repo_root,
+            branch,
+            tmux_session_name,
+        } => {
+            if let Err(message) = worktree::check_branch_name(&repo_root, &branch).await {
+                show_toast(state, message);
+                return;
+            }
+            let config = crate::config::load_config(state.config.exit_on_switch);
+            let repo_root = worktree::main_root(&repo_root).await;
+            let path = worktree::worktree_path(config.worktree_dir.as_deref(), &repo_root, &branch);
+            if let Err(e) = worktree::add(&repo_root, &branch, &path).await {
+                show_toast(state, format!("Could not add worktree: {}", e));
+                return;
+            }
+            let cwd = path.to_string_lossy().into_owned();
+            let tmux = TmuxClient::new(&config);
+            let Ok(Some(pane_info)) = tmux.create_window(&tmux_session_name, Some(&cwd)).await
+            else {
+                show_toast(state, format!("Could not open a window in {}", cwd));
+                return;
+            };
+            // Recorded before switching, since switching may quit
+            state
+                .session_worktrees
+                .insert(pane_info.pane_id.clone(), cwd.clone());
+            persist_state(state);
+            let _ = tmux.switch_to_pane(&pane_info.pane_target).await;
+            let agent = config.command_agent();
+            track_new_window(state, agent, pane_info, Some(cwd), selected_pane_target);
+        }
+        Action::RemoveWorktrees(paths) => {
+            let mut removed = 0;
+            let mut failed = Vec::new();
+            for path in &paths {
+                match worktree::remove(path).await {
+                    Ok(()) => removed += 1,
+                    Err(e) => failed.push((path, e)),
+                }
+            }
+            let message = match (removed, failed.as_slice()) {
+                (1, []) => format!("Removed worktree {}", paths[0].display()),
+                (n, []) => format!("Removed {} worktrees", n),
+                (0, [(path, e)]) => format!("Could not remove {}: {}", path.display(), e),
+                (n, failed) => {
+                    let names: Vec<String> = failed
+                        .iter()
+                        .map(|(path, _)| path.display().to_string())
+                        .collect();
+                    format!("Removed {}, failed: {}", n, names.join(", "))
+                }
+            };
+            show_toast(state, message);
+        }
+        Action::ShowDiff { pane_id, cwd } => {
+            if let Some((_, task)) = state.diff_loading.take() {
+                task.abort();
+            }
+            let task = crate::diff::spawn_load(tx.clone(), pane_id.clone(), cwd);
+            state.diff_loading = Some((pane_id, task));
+        }
+        Action::KillPane(target) => {
+            let worktrees = take_worktrees(state, std::slice::from_ref(&target));
+            kill_pane(state, &target).await;
+            offer_worktree_removal(state, worktrees).await;
+            persist_state(state);
+            let old_items = std::mem::take(&mut state.visible_items);
+            refresh_visible_items(state);
+            state.selected_index =
+                resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+            update_selected_target(state, selected_pane_target);
+        }
+        Action::RenameTmuxSession { from, to } => {
+            let tmux = TmuxClient::new(&state.config);
+            if tmux.rename_session(&from, &to).await.is_err() {
+                show_toast(state, format!("Could not rename {}", from));
+                return;
+            }
+            rename_group_key(state, &from, &to);
+            for session in state
+                .sessions
+                .iter_mut()
+                .filter(|s| s.tmux_session_name == from)
+            {
+                if let Some(rest) = session.pane_target.strip_prefix(&format!("{}:", from)) {
+                    session.pane_target = format!("{}:{}", to, rest);
+                }
+                session.tmux_session_name = to.clone();
+            }
+            state.display_name_map.remove(&from);
+            state.display_name_map.insert(to.clone(), to);
+            persist_state(state);
+            let old_items = std::mem::take(&mut state.visible_items);
+            refresh_visible_items(state);
+            state.selected_index =
+                resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+            update_selected_target(state, selected_pane_target);
+        }
+        Action::KillPanes(targets) => {
+            let worktrees = take_worktrees(state, &targets);
+            for target in &targets {
+                kill_pane(state, target).await;
+            }
+            state.marked_pane_ids.clear();
+            state.visual_anchor = None;
+            show_toast(state, format!("Closed {} sessions", targets.len()));
+            offer_worktree_removal(state, worktrees).await;
+            persist_state(state);
+            let old_items = std::mem::take(&mut state.visible_items);
+            refresh_visible_items(state);
+            state.selected_index =
+                resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+            update_selected_target(state, selected_pane_target);
+        }
+        Action::SendPrompt {
+            pane_id,
+            pane_target,
+            text,
+        } => {
+            if session_is_busy(state, &pane_id) {
+                enqueue_prompt(state, &pane_id, text);
+                return;
+            }
+            match crate::tmux::send_prompt(&pane_target, &text).await {
+                Ok(()) => {
+                    state.unread_pane_ids.remove(&pane_id);
+                    state.unread_order.remove(&pane_id);
+                    state.paused_queues.remove(&pane_id);
+                    persist_state(state);
+                    refresh_visible_items(state);
+                    show_toast(state, "Prompt sent".to_string());
+                }
+                Err(_) => show_toast(state, "Send failed".to_string()),
+            }
+        }
+        Action::Broadcast { pane_ids, text } => {
+            let mut sent = Vec::new();
+            let mut queued = 0;
+            let mut skipped = 0;
+            let mut awaiting = 0;
+            let mut failed = Vec::new();
+            let mut set = tokio::task::JoinSet::new();
+            for pane_id in pane_ids {
+                let Some(session) = state.sessions.iter().find(|s| s.pane_id == pane_id) else {
+                    skipped += 1;
+                    continue;
+                };
+                // An agent waiting on a question or plan counts as busy, so the
+                // broadcast can't answer it
+                let awaiting_answer = session.status == SessionStatus::Idle
+                    && state
+                        .prompt_states
+                        .get(&pane_id)
+                        .is_some_and(|p| *p != PromptState::None);
+                if awaiting_answer {
+                    awaiting += 1;
+                }
+                match session.status {
+                    SessionStatus::Exited => skipped += 1,
+                    SessionStatus::Idle if !awaiting_answer => {
+                        let label = session_label(state, session);
+                        let pane_target = session.pane_target.clone();
+                        let text = text.clone();
+                        set.spawn(async move {
+                            let result = crate::tmux::send_prompt(&pane_target, &text).await;
+                            (pane_id, label, result.is_ok())
+                        });
+                    }
+                    SessionStatus::Active | SessionStatus::Idle => {
+                        match state.config.broadcast_busy {
+                            BroadcastBusyMode::Queue => {
+                                state
+                                    .prompt_queues
+                                    .entry(pane_id)
+                                    .or_default()
+                                    .push(text.clone());
+                                queued += 1;
+                            }
+                            BroadcastBusyMode::Skip => skipped += 1,
+                        }
+                    }
+                }
+            }
+            while let Some(Ok((pane_id, label, ok))) = set.join_next().await {
+                if ok {
+                    sent.push(pane_id);
+                } else {
+                    failed.push(label);
+                }
+            }
+            for pane_id in &sent {
+                state.unread_pane_ids.remove(pane_id);
+                state.unread_order.remove(pane_id);
+                state.paused_queues.remove(pane_id);
+            }
+            persist_state(state);
+            refresh_visible_items(state);
+
+            let mut parts = vec![format!("Sent {}", sent.len())];
+            if queued > 0 {
+                parts.push(format!("queued {}", queued));
+            }
+            if skipped > 0 {
+                parts.push(format!("skipped {}", skipped));
+            }
+            if awaiting > 0 {
+                parts.push(format!("{} awaiting an answer", awaiting));
+            }
+            if !failed.is_empty() {
+                failed.sort();
+                parts.push(format!("failed: {}", failed.join(", ")));
+            }
+            show_toast(state, parts.join(" · "));
+        }
+        Action::Interrupt {
+            pane_id,
+            pane_target,
+            agent,
+            label,
+        } => match crate::tmux::send_interrupt(&pane_target, agent).await {
+            Ok(()) => {
+                if state.prompt_queues.contains_key(&pane_id) {
+                    state.paused_queues.insert(pane_id.clone());
+                }
+                state
+                    .pending_interrupts
+                    .insert(pane_id, (label.clone(), now_millis()));
+                show_toast(state, format!("Interrupting {}", label));
+            }
+            Err(_) => show_toast(state, "Interrupt failed".to_string()),
+        },
+        Action::ComposeInEditor(_) => {}
+        Action::ResumeSession(entry) => {
+            let tmux = TmuxClient::new(&state.config);
+            let command = history::resume_command(
+                &state.config.command_for(entry.agent),
+                entry.agent,
+                &entry.session_id,
+            );
+            let mut created = tmux
+                .create_window_with_command(
+                    &entry.tmux_session_name,
+                    entry.cwd.as_deref(),
+                    &command,
+                )
+                .await;
+            // The original tmux session may be gone; fall back to the one we run in
+            if created.is_err() {
+                if let Some((_, current_session)) = tmux.get_focused_pane_info().await {
+                    created = tmux
+                        .create_window_with_command(
+                            &current_session,
+                            entry.cwd.as_deref(),
+                            &command,
+                        )
+                        .await;
+                }
+            }
+            match created {
+                Ok(Some(pane_info)) => {
+                    state.history.retain(|e| e.session_id != entry.session_id);
+                    history::save_history(&state.history);
                     let _ = tmux.switch_to_pane(&pane_info.pane_target).await;
                     if state.config.exit_on_switch {
                         state.should_quit = true;
                     } else {
-                        let inferred_agent = if config.command.ends_with("opencode") {
-                            Agent::Opencode
-                        } else {
-                            Agent::Claude
-                        };
-                        let new_session = AgentSession {
-                            pane_id: pane_info.pane_id,
-                            pane_target: pane_info.pane_target,
-                            title: pane_info.pane_title.clone(),
-                            tmux_session_name: pane_info.tmux_session_name.clone(),
-                            status: crate::session::parse_session_status(
-                                inferred_agent,
-                                &pane_info.pane_title,
-                                None,
-                            ),
-                            agent: inferred_agent,
-                            session_id: None,
-                            cwd: None,
-                            model: None,
-                            agent_role: None,
-                        };
-                        state
-                            .prev_status_map
-                            .insert(new_session.pane_id.clone(), new_session.status.clone());
-                        state.sessions.push(new_session);
-                        persist_state(state);
                         let old_items = std::mem::take(&mut state.visible_items);
                         refresh_visible_items(state);
                         state.selected_index = resolve_selected_index(
@@ -427,25 +1101,24 @@ async fn process_action(
                         update_selected_target(state, selected_pane_target);
                     }
                 }
+                _ => show_toast(state, "Resume failed".to_string()),
             }
         }
-        Action::KillPane(target) => {
-            let config = crate::config::load_config(false);
-            let tmux = TmuxClient::new(&config);
-            let _ = tmux.kill_pane(&target).await;
-            if let Some(removed) = state.sessions.iter().find(|s| s.pane_target == target) {
-                let pane_id = removed.pane_id.clone();
-                state.prev_status_map.remove(&pane_id);
-                state.unread_pane_ids.remove(&pane_id);
-                state.unread_order.remove(&pane_id);
-            }
-            state.sessions.retain(|s| s.pane_target != target);
-            persist_state(state);
-            let old_items = std::mem::take(&mut state.visible_items);
-            refresh_visible_items(state);
-            state.selected_index =
-                resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
-            update_selected_target(state, selected_pane_target);
+        Action::RespawnAgent {
+            pane_target,
+            cwd,
+            agent,
+        } => {
+            let command = state.config.command_for(agent);
+            let tmux = TmuxClient::new(&state.config);
+            let message = match tmux
+                .respawn_pane(&pane_target, cwd.as_deref(), &command)
+                .await
+            {
+                Ok(()) => format!("Respawning {}", agent.binary()),
+                Err(_) => "Respawn failed".to_string(),
+            };
+            show_toast(state, message);
         }
         Action::ForwardScrollDown { target, col, row } => {
             tokio::spawn(async move {
@@ -464,9 +1137,26 @@ fn handle_message(
     state: &mut AppState,
     msg: Message,
     selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+    tx: &mpsc::UnboundedSender<Message>,
 ) {
     match msg {
-        Message::SessionsUpdated(sessions, display_names, prompt_states) => {
+        Message::SessionsUpdated {
+            mut sessions,
+            display_names,
+            prompt_states,
+            last_output_at,
+            live_pane_ids,
+        } => {
+            // A failed `tmux list-panes` reports no panes at all. Reconciling
+            // against that would forget exited sessions, queues and
+            // annotations and record every session as ended, so that
+            // bookkeeping waits for the next good poll
+            let discovered = !live_pane_ids.is_empty();
+            if discovered {
+                merge_exited_sessions(state, &mut sessions, &live_pane_ids);
+            }
+            record_ended_sessions(state, &sessions, &live_pane_ids);
+
             // Update unread tracking
             let mut next_unread = state.unread_pane_ids.clone();
             let current_pane_ids: HashSet<String> =
@@ -475,8 +1165,14 @@ fn handle_message(
             for session in &sessions {
                 if let Some(prev_status) = state.prev_status_map.get(&session.pane_id) {
                     if *prev_status == SessionStatus::Active
-                        && session.status == SessionStatus::Idle
+                        && session.status != SessionStatus::Active
                     {
+                        if state.pending_interrupts.contains_key(&session.pane_id) {
+                            continue;
+                        }
+                        if deliver_queued_prompt(state, session, &prompt_states, tx) {
+                            continue;
+                        }
                         next_unread.insert(session.pane_id.clone());
                         state.unread_counter += 1;
                         state
@@ -486,11 +1182,84 @@ fn handle_message(
                 }
             }
 
+            // Queues restored from disk or re-queued after a failed send have
+            // no busy to idle edge to wait for, so idle sessions drain them on
+            // any poll
+            for session in &sessions {
+                if session.status == SessionStatus::Active {
+                    state.queued_prompt_sent_at.remove(&session.pane_id);
+                } else if !state.pending_interrupts.contains_key(&session.pane_id) {
+                    deliver_queued_prompt(state, session, &prompt_states, tx);
+                }
+            }
+
             // Remove unread for panes that no longer exist
-            next_unread.retain(|id| current_pane_ids.contains(id));
-            state
-                .unread_order
-                .retain(|id, _| current_pane_ids.contains(id));
+            if discovered {
+                next_unread.retain(|id| current_pane_ids.contains(id));
+                state
+                    .unread_order
+                    .retain(|id, _| current_pane_ids.contains(id));
+                state
+                    .prompt_queues
+                    .retain(|id, _| current_pane_ids.contains(id));
+                state
+                    .queued_prompt_sent_at
+                    .retain(|id, _| current_pane_ids.contains(id));
+                let queues = &state.prompt_queues;
+                state.paused_queues.retain(|id| queues.contains_key(id));
+                state
+                    .marked_pane_ids
+                    .retain(|id| current_pane_ids.contains(id));
+
+                rekey_session_annotations(state, &sessions, &current_pane_ids);
+                // A new window may not show an agent yet, so worktree owners
+                // are only dropped once the pane itself is gone
+                state
+                    .session_worktrees
+                    .retain(|id, _| live_pane_ids.contains(id));
+            }
+
+            report_interrupts(state, &sessions);
+
+            // Record when each pane last changed status (or first appeared)
+            let now = now_millis();
+            for session in &sessions {
+                let changed = state.prev_status_map.get(&session.pane_id) != Some(&session.status);
+                if changed || !state.status_changed_at.contains_key(&session.pane_id) {
+                    state.status_changed_at.insert(session.pane_id.clone(), now);
+                }
+            }
+            if discovered {
+                state
+                    .status_changed_at
+                    .retain(|id, _| current_pane_ids.contains(id));
+            }
+
+            // Output timestamps come from the poller alone: the preview only
+            // shows the selected pane, and switching panes changes it
+            state.last_output_at = last_output_at;
+
+            // Flag busy sessions whose output hasn't changed for too long. Newly stuck
+            // sessions raise an unread alert; one that recovers on its own drops it.
+            let next_stuck = detect_stuck_sessions(state, &sessions, now);
+            for session in &sessions {
+                let pane_id = &session.pane_id;
+                let was_stuck = state.stuck_pane_ids.contains(pane_id);
+                let is_stuck = next_stuck.contains(pane_id);
+                if is_stuck && !was_stuck {
+                    next_unread.insert(pane_id.clone());
+                    state.unread_counter += 1;
+                    state
+                        .unread_order
+                        .insert(pane_id.clone(), state.unread_counter);
+                } else if was_stuck && !is_stuck && session.status == SessionStatus::Active {
+                    next_unread.remove(pane_id);
+                    state.unread_order.remove(pane_id);
+                }
+            }
+            if discovered {
+                state.stuck_pane_ids = next_stuck;
+            }
 
             // Update prev status map
             let mut next_status_map = HashMap::new();
@@ -501,7 +1270,9 @@ fn handle_message(
             state.sessions = sessions;
             state.display_name_map = display_names;
             state.prompt_states = prompt_states;
-            state.prev_status_map = next_status_map;
+            if discovered {
+                state.prev_status_map = next_status_map;
+            }
             state.unread_pane_ids = next_unread;
 
             // Persist state
@@ -519,8 +1290,64 @@ fn handle_message(
 
             update_selected_target(state, selected_pane_target);
         }
+        Message::GitStatusChanged(statuses) => {
+            state.git_status = statuses;
+        }
+        Message::DiffLoaded { pane_id, diff } => {
+            // Drop answers for a selection that has moved on or a closed view
+            if state
+                .diff_loading
+                .as_ref()
+                .is_none_or(|(loading, _)| *loading != pane_id)
+            {
+                return;
+            }
+            state.diff_loading = None;
+            let diff = match diff {
+                Ok(diff) => diff,
+                Err(message) => {
+                    close_diff(state, selected_pane_target);
+                    show_toast(state, message);
+                    return;
+                }
+            };
+            let same_repo = state
+                .diff_view
+                .as_ref()
+                .is_some_and(|v| v.repo_root == diff.repo_root);
+            state.preview_content = if diff.text.is_empty() {
+                format!("No changes in {}", diff.repo_root.display())
+            } else {
+                diff.text
+            };
+            if !same_repo {
+                state.preview_scroll_offset = 0;
+            }
+            state.preview_is_sticky_bottom = false;
+            state.preview_selection = None;
+            state.copy_mode = None;
+            state.diff_view = Some(DiffView {
+                pane_id,
+                repo_root: diff.repo_root,
+                file_starts: diff.file_starts,
+            });
+        }
+        Message::QueuedPromptFailed { pane_id, text } => {
+            // Back to the front, to be retried on a later poll while idle
+            state
+                .prompt_queues
+                .entry(pane_id)
+                .or_default()
+                .insert(0, text);
+            persist_state(state);
+            refresh_visible_items(state);
+            show_toast(
+                state,
+                "Could not send queued prompt; kept in queue".to_string(),
+            );
+        }
         Message::PreviewUpdated(content) => {
-            if state.copy_mode.is_some() {
+            if state.copy_mode.is_some() || state.diff_view.is_some() {
                 return;
             }
             if !state
@@ -535,20 +1362,230 @@ fn handle_message(
     }
 }
 
-fn apply_text_input(query: &mut String, cursor: &mut usize, key: KeyEvent) -> bool {
-    match (key.code, key.modifiers) {
-        (KeyCode::Char('a'), KeyModifiers::CONTROL) => {
-            *cursor = 0;
-            false
-        }
-        (KeyCode::Char('e'), KeyModifiers::CONTROL) => {
-            *cursor = query.chars().count();
-            false
-        }
-        (KeyCode::Char('u'), KeyModifiers::CONTROL) | (KeyCode::Backspace, KeyModifiers::SUPER) => {
-            let byte_offset = query
-                .char_indices()
-                .nth(*cursor)
+/// Opens the new window in the dashboard's own terminal, or quits when
+/// `exit_on_switch` is set, and lists it until the next poll picks it up.
+fn track_new_window(
+    state: &mut AppState,
+    agent: Agent,
+    pane_info: crate::tmux::CreatedPaneInfo,
+    cwd: Option<String>,
+    selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+) {
+    if state.config.exit_on_switch {
+        state.should_quit = true;
+        return;
+    }
+    let new_session = AgentSession {
+        pane_id: pane_info.pane_id,
+        pane_target: pane_info.pane_target,
+        title: pane_info.pane_title.clone(),
+        tmux_session_name: pane_info.tmux_session_name.clone(),
+        status: crate::session::parse_session_status(agent, &pane_info.pane_title, None),
+        agent,
+        session_id: None,
+        cwd,
+        model: None,
+        agent_role: None,
+        window_name: None,
+        command: None,
+    };
+    state
+        .prev_status_map
+        .insert(new_session.pane_id.clone(), new_session.status.clone());
+    state
+        .status_changed_at
+        .insert(new_session.pane_id.clone(), now_millis());
+    state.sessions.push(new_session);
+    persist_state(state);
+    let old_items = std::mem::take(&mut state.visible_items);
+    refresh_visible_items(state);
+    state.selected_index =
+        resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+    update_selected_target(state, selected_pane_target);
+}
+
+/// Drops the worktree ownership of the panes at `targets`, returning the
+/// worktrees they owned.
+fn take_worktrees(state: &mut AppState, targets: &[String]) -> Vec<PathBuf> {
+    let pane_ids: Vec<String> = state
+        .sessions
+        .iter()
+        .filter(|s| targets.contains(&s.pane_target))
+        .map(|s| s.pane_id.clone())
+        .collect();
+    pane_ids
+        .iter()
+        .filter_map(|id| state.session_worktrees.remove(id))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// After killing their sessions, asks to remove the clean `worktrees` and
+/// warns about dirty ones, which are kept. Worktrees another pane still owns
+/// are left alone.
+async fn offer_worktree_removal(state: &mut AppState, worktrees: Vec<PathBuf>) {
+    let mut clean = Vec::new();
+    let mut dirty = Vec::new();
+    for path in worktrees {
+        let path_str = path.to_string_lossy();
+        if state.session_worktrees.values().any(|p| *p == path_str) || clean.contains(&path) {
+            continue;
+        }
+        match worktree::is_clean(&path).await {
+            Some(true) => clean.push(path),
+            Some(false) => dirty.push(path),
+            None => {}
+        }
+    }
+    match dirty.as_slice() {
+        [] => {}
+        [path] => show_toast(
+            state,
+            format!("Kept worktree {}: uncommitted changes", path.display()),
+        ),
+        paths => show_toast(
+            state,
+            format!("Kept {} worktrees with uncommitted changes", paths.len()),
+        ),
+    }
+    if !clean.is_empty() {
+        state.pending_confirm = Some(PendingConfirm::RemoveWorktrees(clean));
+    }
+}
+
+/// Kills the pane and forgets its session, recording it in the history so
+/// it can be resumed. The caller persists and refreshes.
+async fn kill_pane(state: &mut AppState, target: &str) {
+    let tmux = TmuxClient::new(&state.config);
+    let _ = tmux.kill_pane(target).await;
+    if let Some(removed) = state.sessions.iter().find(|s| s.pane_target == target) {
+        let pane_id = removed.pane_id.clone();
+        if removed.status != SessionStatus::Exited {
+            if let Some(entry) = HistoryEntry::from_session(removed, now_millis()) {
+                history::record(&mut state.history, entry);
+                history::save_history(&state.history);
+            }
+        }
+        state.prev_status_map.remove(&pane_id);
+        state.status_changed_at.remove(&pane_id);
+        state.unread_pane_ids.remove(&pane_id);
+        state.unread_order.remove(&pane_id);
+        state.marked_pane_ids.remove(&pane_id);
+    }
+    state.sessions.retain(|s| s.pane_target != target);
+    state.exited_sessions.retain(|s| s.pane_target != target);
+}
+
+/// Keeps sessions whose agent exited while the pane stayed open, marking them
+/// `Exited` and appending them to `sessions`. Entries are dropped once the pane
+/// closes or an agent is detected in it again.
+fn merge_exited_sessions(
+    state: &mut AppState,
+    sessions: &mut Vec<AgentSession>,
+    live_pane_ids: &HashSet<String>,
+) {
+    let agent_pane_ids: HashSet<&str> = sessions.iter().map(|s| s.pane_id.as_str()).collect();
+    for prev in &state.sessions {
+        if prev.status == SessionStatus::Exited
+            || agent_pane_ids.contains(prev.pane_id.as_str())
+            || !live_pane_ids.contains(&prev.pane_id)
+        {
+            continue;
+        }
+        let mut exited = prev.clone();
+        exited.status = SessionStatus::Exited;
+        state.exited_sessions.push(exited);
+    }
+    state.exited_sessions.retain(|s| {
+        live_pane_ids.contains(&s.pane_id) && !agent_pane_ids.contains(s.pane_id.as_str())
+    });
+    sessions.extend(state.exited_sessions.iter().cloned());
+}
+
+/// Records sessions that exited or whose pane closed since the last poll so
+/// they can be resumed from the "Recently closed" section.
+fn record_ended_sessions(
+    state: &mut AppState,
+    sessions: &[AgentSession],
+    live_pane_ids: &HashSet<String>,
+) {
+    let ended = history::ended_sessions(&state.sessions, sessions, live_pane_ids, now_millis());
+    if ended.is_empty() {
+        return;
+    }
+    for entry in ended {
+        history::record(&mut state.history, entry);
+    }
+    history::save_history(&state.history);
+}
+
+/// Reports interrupted panes that went idle, and ones still busy once the
+/// interrupt has had time to take effect.
+fn report_interrupts(state: &mut AppState, sessions: &[AgentSession]) {
+    let now = now_millis();
+    let mut messages = Vec::new();
+    state
+        .pending_interrupts
+        .retain(|pane_id, (label, sent_at)| {
+            match sessions.iter().find(|s| &s.pane_id == pane_id) {
+                Some(s) if s.status == SessionStatus::Active => {
+                    if now.saturating_sub(*sent_at) < INTERRUPT_TIMEOUT_MS {
+                        return true;
+                    }
+                    messages.push(format!("{} is still busy", label));
+                }
+                Some(_) => match state.prompt_queues.get(pane_id) {
+                    Some(queue) if state.paused_queues.contains(pane_id) => messages.push(format!(
+                        "Interrupted {} · {} queued prompts paused",
+                        label,
+                        queue.len()
+                    )),
+                    _ => messages.push(format!("Interrupted {}", label)),
+                },
+                None => {}
+            }
+            false
+        });
+    if !messages.is_empty() {
+        show_toast(state, messages.join(" · "));
+    }
+}
+
+fn detect_stuck_sessions(state: &AppState, sessions: &[AgentSession], now: u64) -> HashSet<String> {
+    let threshold_ms = state.config.stuck_threshold_secs * 1000;
+    if threshold_ms == 0 {
+        return HashSet::new();
+    }
+    sessions
+        .iter()
+        .filter(|s| s.status == SessionStatus::Active)
+        .filter(|s| {
+            let last_activity = state
+                .last_output_at
+                .get(&s.pane_id)
+                .copied()
+                .max(state.status_changed_at.get(&s.pane_id).copied())
+                .unwrap_or(now);
+            now.saturating_sub(last_activity) > threshold_ms
+        })
+        .map(|s| s.pane_id.clone())
+        .collect()
+}
+
+fn apply_text_input(query: &mut String, cursor: &mut usize, key: KeyEvent) -> bool {
+    match (key.code, key.modifiers) {
+        (KeyCode::Char('a'), KeyModifiers::CONTROL) => {
+            *cursor = 0;
+            false
+        }
+        (KeyCode::Char('e'), KeyModifiers::CONTROL) => {
+            *cursor = query.chars().count();
+            false
+        }
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) | (KeyCode::Backspace, KeyModifiers::SUPER) => {
+            let byte_offset = query
+                .char_indices()
+                .nth(*cursor)
                 .map(|(i, _)| i)
                 .unwrap_or(query.len());
             query.drain(..byte_offset);
@@ -680,14 +1717,46 @@ fn handle_key_event(
     selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
 ) -> Option<Action> {
     // Confirm dialog takes priority over all other input
-    if state.pending_confirm_target.is_some() {
+    if let Some(PendingConfirm::KillPanes { targets, typed }) = &mut state.pending_confirm {
+        if targets.len() >= state.config.bulk_kill_confirm_threshold {
+            match key.code {
+                KeyCode::Char(c) => {
+                    typed.push(c);
+                    return None;
+                }
+                KeyCode::Backspace => {
+                    typed.pop();
+                    return None;
+                }
+                KeyCode::Enter if typed != "kill" => return None,
+                _ => {}
+            }
+        }
+    }
+    if state.pending_confirm.is_some() {
         match key.code {
             KeyCode::Enter => {
-                let target = state.pending_confirm_target.take().unwrap();
-                return Some(Action::KillPane(target));
+                return match state.pending_confirm.take().unwrap() {
+                    PendingConfirm::KillPane(target) => Some(Action::KillPane(target)),
+                    PendingConfirm::KillPanes { targets, .. } => Some(Action::KillPanes(
+                        targets.into_iter().map(|(target, _)| target).collect(),
+                    )),
+                    PendingConfirm::Interrupt {
+                        pane_id,
+                        pane_target,
+                        agent,
+                        label,
+                    } => Some(Action::Interrupt {
+                        pane_id,
+                        pane_target,
+                        agent,
+                        label,
+                    }),
+                    PendingConfirm::RemoveWorktrees(paths) => Some(Action::RemoveWorktrees(paths)),
+                };
             }
             KeyCode::Esc => {
-                state.pending_confirm_target = None;
+                state.pending_confirm = None;
                 return None;
             }
             _ => return None,
@@ -727,6 +1796,22 @@ fn handle_key_event(
         }
     }
 
+    if state.prompt_composer.is_some() {
+        return handle_prompt_composer_key(state, key);
+    }
+    if state.queue_editor.is_some() {
+        return handle_queue_editor_key(state, key);
+    }
+    if state.command_palette.is_some() {
+        return handle_command_palette_key(state, key);
+    }
+    if state.launch_picker.is_some() {
+        return handle_launch_picker_key(state, key);
+    }
+    if state.line_input.is_some() {
+        return handle_line_input_key(state, key, selected_pane_target);
+    }
+
     // Session filter input mode
     if state.session_filter_active {
         match key.code {
@@ -757,7 +1842,9 @@ fn handle_key_event(
                         } => Some(tmux_session_name.clone()),
                         VisibleItem::SubgroupHeader { .. }
                         | VisibleItem::HiddenHeader { .. }
-                        | VisibleItem::GroupHiddenHeader { .. } => None,
+                        | VisibleItem::GroupHiddenHeader { .. }
+                        | VisibleItem::RecentHeader { .. }
+                        | VisibleItem::RecentSession { .. } => None,
                     };
                     if let Some(target) = target {
                         if state.config.exit_on_switch {
@@ -813,9 +1900,30 @@ fn handle_key_event(
             None
         }
         KeyCode::Char('1') => {
+            if matches!(state.focus, Focus::Sessions) && state.active_view.is_some() {
+                select_view(state, None, selected_pane_target);
+            }
             state.focus = Focus::Sessions;
             None
         }
+        KeyCode::Char(c @ '2'..='9') if matches!(state.focus, Focus::Sessions) => {
+            let index = c as usize - '2' as usize;
+            if index < state.all_views().len() {
+                select_view(state, Some(index), selected_pane_target);
+            }
+            None
+        }
+        KeyCode::Char('S') if matches!(state.focus, Focus::Sessions) => {
+            let editing = state
+                .active_view
+                .and_then(|i| i.checked_sub(state.config.views.len()))
+                .filter(|&i| i < state.saved_views.len());
+            let text = editing
+                .map(|i| state.saved_views[i].name.clone())
+                .unwrap_or_default();
+            state.line_input = Some(LineInput::new(LineInputPurpose::SaveView { editing }, text));
+            None
+        }
         KeyCode::Char('0') => {
             state.sessions_expanded = false;
             state.focus = Focus::Preview;
@@ -872,10 +1980,13 @@ fn handle_key_event(
                             refresh_visible_items(state);
                             persist_ui_state(state);
                         }
-                        VisibleItem::GroupHiddenHeader {
-                            tmux_session_name, ..
-                        } => {
-                            let name = tmux_session_name.clone();
+                        VisibleItem::RecentHeader { .. } => {
+                            state.recent_section_collapsed = !state.recent_section_collapsed;
+                            refresh_visible_items(state);
+                            persist_ui_state(state);
+                        }
+                        VisibleItem::GroupHiddenHeader { group_key, .. } => {
+                            let name = group_key.clone();
                             if !state.group_hidden_collapsed.remove(&name) {
                                 state.group_hidden_collapsed.insert(name);
                             }
@@ -900,11 +2011,11 @@ fn handle_key_event(
                             update_selected_target(state, selected_pane_target);
                         }
                         VisibleItem::GroupHeader {
-                            tmux_session_name,
+                            group_key,
                             in_hidden_section,
                             ..
                         } => {
-                            let group_name = tmux_session_name.clone();
+                            let group_name = group_key.clone();
                             let set = if *in_hidden_section {
                                 &mut state.collapsed_hidden_groups
                             } else {
@@ -925,34 +2036,59 @@ fn handle_key_event(
         }
         KeyCode::Char('H') => {
             if matches!(state.focus, Focus::Sessions) {
+                let bulk = bulk_pane_ids(state);
+                if !bulk.is_empty() {
+                    // Unhide when every marked session is hidden, hide otherwise
+                    let all_hidden = state
+                        .sessions
+                        .iter()
+                        .filter(|s| bulk.contains(&s.pane_id))
+                        .all(|s| {
+                            state.hidden_pane_ids.contains(&s.pane_id)
+                                || state.hidden_groups.contains(group_key_of(state, s))
+                        });
+                    for pane_id in &bulk {
+                        if all_hidden {
+                            state.hidden_pane_ids.remove(pane_id);
+                        } else {
+                            state.hidden_pane_ids.insert(pane_id.clone());
+                        }
+                    }
+                    state.marked_pane_ids.clear();
+                    state.visual_anchor = None;
+                    hide_toggle_refresh(state, selected_pane_target);
+                    return None;
+                }
                 match state.visible_items.get(state.selected_index).cloned() {
                     Some(VisibleItem::Session { session, .. }) => {
                         let pane_id = session.pane_id.clone();
-                        if state.hidden_groups.contains(&session.tmux_session_name) {
+                        let group_name = group_key_of(state, &session).to_string();
+                        if state.hidden_groups.contains(&group_name) {
                             // Unhide this session from a group-level hide:
                             // remove group hide, individually hide all siblings instead
-                            let group_name = session.tmux_session_name.clone();
                             state.hidden_groups.remove(&group_name);
-                            for s in &state.sessions {
-                                if s.tmux_session_name == group_name && s.pane_id != pane_id {
-                                    state.hidden_pane_ids.insert(s.pane_id.clone());
-                                }
-                            }
+                            let siblings: Vec<String> = state
+                                .sessions
+                                .iter()
+                                .filter(|s| group_key_of(state, s) == group_name)
+                                .filter(|s| s.pane_id != pane_id)
+                                .map(|s| s.pane_id.clone())
+                                .collect();
+                            state.hidden_pane_ids.extend(siblings);
                         } else if !state.hidden_pane_ids.remove(&pane_id) {
                             state.hidden_pane_ids.insert(pane_id);
                         }
                         hide_toggle_refresh(state, selected_pane_target);
                     }
-                    Some(VisibleItem::GroupHeader {
-                        tmux_session_name, ..
-                    }) => {
-                        let group_name = tmux_session_name.clone();
-                        state.hidden_pane_ids.retain(|pid| {
-                            !state
-                                .sessions
-                                .iter()
-                                .any(|s| s.tmux_session_name == group_name && s.pane_id == *pid)
-                        });
+                    Some(VisibleItem::GroupHeader { group_key, .. }) => {
+                        let group_name = group_key.clone();
+                        let members: HashSet<String> = state
+                            .sessions
+                            .iter()
+                            .filter(|s| group_key_of(state, s) == group_name)
+                            .map(|s| s.pane_id.clone())
+                            .collect();
+                        state.hidden_pane_ids.retain(|pid| !members.contains(pid));
                         if !state.hidden_groups.remove(&group_name) {
                             state.hidden_groups.insert(group_name);
                         }
@@ -1016,11 +2152,11 @@ fn handle_key_event(
                             update_selected_target(state, selected_pane_target);
                         }
                         VisibleItem::GroupHeader {
-                            tmux_session_name,
+                            group_key,
                             in_hidden_section,
                             ..
                         } => {
-                            let name = tmux_session_name.clone();
+                            let name = group_key.clone();
                             if *in_hidden_section {
                                 state.collapsed_hidden_groups.remove(&name);
                             } else {
@@ -1035,12 +2171,17 @@ fn handle_key_event(
                             refresh_visible_items(state);
                             persist_ui_state(state);
                         }
+                        VisibleItem::RecentHeader { is_collapsed, .. } if *is_collapsed => {
+                            state.recent_section_collapsed = false;
+                            refresh_visible_items(state);
+                            persist_ui_state(state);
+                        }
                         VisibleItem::GroupHiddenHeader {
-                            tmux_session_name,
+                            group_key,
                             is_collapsed,
                             ..
                         } if *is_collapsed => {
-                            let name = tmux_session_name.clone();
+                            let name = group_key.clone();
                             state.group_hidden_collapsed.remove(&name);
                             refresh_visible_items(state);
                             persist_ui_state(state);
@@ -1067,7 +2208,9 @@ fn handle_key_event(
                     } => Some(tmux_session_name.clone()),
                     VisibleItem::SubgroupHeader { .. }
                     | VisibleItem::HiddenHeader { .. }
-                    | VisibleItem::GroupHiddenHeader { .. } => None,
+                    | VisibleItem::GroupHiddenHeader { .. }
+                    | VisibleItem::RecentHeader { .. }
+                    | VisibleItem::RecentSession { .. } => None,
                 };
                 if let Some(target) = target {
                     if state.config.exit_on_switch {
@@ -1080,6 +2223,18 @@ fn handle_key_event(
         }
         KeyCode::Char('r') => {
             if matches!(state.focus, Focus::Sessions) {
+                let bulk = bulk_pane_ids(state);
+                if !bulk.is_empty() {
+                    for pane_id in &bulk {
+                        state.unread_pane_ids.remove(pane_id);
+                        state.unread_order.remove(pane_id);
+                    }
+                    state.marked_pane_ids.clear();
+                    state.visual_anchor = None;
+                    persist_state(state);
+                    refresh_visible_items(state);
+                    return None;
+                }
                 if let Some(VisibleItem::Session { session, .. }) = state
                     .visible_items
                     .get(state.selected_index)
@@ -1104,149 +2259,1197 @@ fn handle_key_event(
                             session.pane_target.clone(),
                         ),
                         VisibleItem::GroupHeader {
-                            tmux_session_name, ..
-                        } => (tmux_session_name.clone(), tmux_session_name.clone()),
+                            group_key,
+                            tmux_session_name,
+                            ..
+                        } => {
+                            let cwd_target = if state.group_by == GroupBy::TmuxSession {
+                                tmux_session_name.clone()
+                            } else {
+                                state
+                                    .sessions
+                                    .iter()
+                                    .find(|s| group_key_of(state, s) == group_key)
+                                    .map(|s| s.pane_target.clone())
+                                    .unwrap_or_else(|| tmux_session_name.clone())
+                            };
+                            (tmux_session_name.clone(), cwd_target)
+                        }
                         VisibleItem::SubgroupHeader { .. }
                         | VisibleItem::HiddenHeader { .. }
-                        | VisibleItem::GroupHiddenHeader { .. } => return None,
+                        | VisibleItem::GroupHiddenHeader { .. }
+                        | VisibleItem::RecentHeader { .. }
+                        | VisibleItem::RecentSession { .. } => return None,
                     };
-                    return Some(Action::CreateSession {
-                        tmux_session_name,
-                        cwd_target,
-                    });
+                    return open_launch_picker(state, &item, tmux_session_name, cwd_target);
                 }
             }
             None
         }
-        KeyCode::Char('x') => {
+        KeyCode::Char('R') => {
             if matches!(state.focus, Focus::Sessions) {
-                if let Some(VisibleItem::Session { session, .. }) = state
-                    .visible_items
-                    .get(state.selected_index)
-                    .cloned()
-                    .as_ref()
+                if let Some(VisibleItem::Session { session, .. }) =
+                    state.visible_items.get(state.selected_index)
                 {
-                    state.pending_confirm_target = Some(session.pane_target.clone());
+                    if session.status == SessionStatus::Exited {
+                        return Some(Action::RespawnAgent {
+                            pane_target: session.pane_target.clone(),
+                            cwd: session.cwd.clone(),
+                            agent: session.agent,
+                        });
+                    }
+                }
+                if let Some(VisibleItem::RecentSession { entry }) =
+                    state.visible_items.get(state.selected_index)
+                {
+                    return Some(Action::ResumeSession(entry.clone()));
                 }
             }
             None
         }
-        KeyCode::Char('/') => {
+        KeyCode::Char('d') => {
             if matches!(state.focus, Focus::Sessions) {
-                state.session_filter_active = true;
-                state.session_filter_query.clear();
-                state.session_filter_cursor = 0;
-            } else if matches!(state.focus, Focus::Preview) && !state.preview_content.is_empty() {
-                state.preview_is_sticky_bottom = false;
-                state.preview_selection = None;
-                let visible_height = state.preview_area_height.saturating_sub(2);
-                let middle_row = (state.preview_scroll_offset + visible_height / 2)
-                    .min(state.preview_content_height.saturating_sub(1));
-                let mut copy = copy_mode::CopyModeState::new(middle_row, 0);
-                copy.search_active = true;
-                copy.search_direction = copy_mode::SearchDirection::Forward;
-                state.copy_mode = Some(copy);
+                if let Some(VisibleItem::Session { session, .. }) =
+                    state.visible_items.get(state.selected_index).cloned()
+                {
+                    if session.status == SessionStatus::Exited {
+                        let pane_id = session.pane_id;
+                        state.exited_sessions.retain(|s| s.pane_id != pane_id);
+                        state.sessions.retain(|s| s.pane_id != pane_id);
+                        state.prev_status_map.remove(&pane_id);
+                        state.status_changed_at.remove(&pane_id);
+                        state.unread_pane_ids.remove(&pane_id);
+                        state.unread_order.remove(&pane_id);
+                        hide_toggle_refresh(state, selected_pane_target);
+                    }
+                }
+                if let Some(VisibleItem::RecentSession { entry }) =
+                    state.visible_items.get(state.selected_index).cloned()
+                {
+                    state.history.retain(|e| e.session_id != entry.session_id);
+                    history::save_history(&state.history);
+                    hide_toggle_refresh(state, selected_pane_target);
+                }
             }
             None
         }
-        KeyCode::Esc => {
-            if matches!(state.focus, Focus::Sessions) && !state.session_filter_query.is_empty() {
-                state.session_filter_query.clear();
-                state.session_filter_cursor = 0;
-                refresh_visible_items(state);
-                state.selected_index = state
-                    .selected_index
-                    .min(state.visible_items.len().saturating_sub(1));
-                update_selected_target(state, selected_pane_target);
+        KeyCode::Char('i') => {
+            if matches!(state.focus, Focus::Sessions) {
+                let composer = if bulk_pane_ids(state).is_empty() {
+                    new_prompt_composer(state)
+                } else {
+                    new_broadcast_composer(state)
+                };
+                if let Some(composer) = composer {
+                    state.prompt_composer = Some(composer);
+                }
             }
             None
         }
-        KeyCode::Char('?') => {
-            if matches!(state.focus, Focus::Preview) && !state.preview_content.is_empty() {
-                state.preview_is_sticky_bottom = false;
-                state.preview_selection = None;
-                let visible_height = state.preview_area_height.saturating_sub(2);
-                let middle_row = (state.preview_scroll_offset + visible_height / 2)
-                    .min(state.preview_content_height.saturating_sub(1));
-                let mut copy = copy_mode::CopyModeState::new(middle_row, 0);
-                copy.search_active = true;
-                copy.search_direction = copy_mode::SearchDirection::Backward;
-                state.copy_mode = Some(copy);
-            } else {
-                state.show_help = !state.show_help;
+        KeyCode::Char('E') => {
+            if matches!(state.focus, Focus::Sessions) {
+                if let Some(composer) = new_prompt_composer(state) {
+                    return Some(Action::ComposeInEditor(composer));
+                }
             }
             None
         }
-        KeyCode::Char('v') => {
-            if !state.preview_content.is_empty() {
-                state.focus = Focus::Preview;
-                state.sessions_expanded = false;
-                state.preview_is_sticky_bottom = false;
-                state.preview_selection = None;
-                let visible_height = state.preview_area_height.saturating_sub(2);
-                let middle_row = (state.preview_scroll_offset + visible_height / 2)
-                    .min(state.preview_content_height.saturating_sub(1));
-                state.copy_mode = Some(copy_mode::CopyModeState::new(middle_row, 0));
+        KeyCode::Char('V') => {
+            if matches!(state.focus, Focus::Sessions) {
+                if state.visual_anchor.is_some() {
+                    // Second press commits the range to the marked set
+                    state.marked_pane_ids = bulk_pane_ids(state);
+                    state.visual_anchor = None;
+                } else if let Some(VisibleItem::Session { session, .. }) =
+                    state.visible_items.get(state.selected_index)
+                {
+                    state.visual_anchor = Some(session.pane_id.clone());
+                }
             }
             None
         }
-        KeyCode::Char('y') => {
-            if matches!(state.focus, Focus::Preview) && !state.preview_content.is_empty() {
-                if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                    let _ = clipboard.set_text(&state.preview_content);
-                    state.toast_message = Some("Copied!".to_string());
-                    state.toast_deadline =
-                        Some(std::time::Instant::now() + std::time::Duration::from_millis(1500));
+        KeyCode::Char('p') => {
+            if matches!(state.focus, Focus::Sessions) {
+                let toggled = match state.visible_items.get(state.selected_index) {
+                    Some(VisibleItem::Session { session, .. }) => {
+                        let key = crate::session::pin_key(session).to_string();
+                        if !state.pinned_sessions.remove(&key) {
+                            state.pinned_sessions.insert(key);
+                        }
+                        true
+                    }
+                    Some(VisibleItem::GroupHeader { group_key, .. }) => {
+                        let group = group_key.clone();
+                        if !state.pinned_groups.remove(&group) {
+                            state.pinned_groups.insert(group);
+                        }
+                        true
+                    }
+                    _ => false,
+                };
+                if toggled {
+                    hide_toggle_refresh(state, selected_pane_target);
                 }
             }
             None
         }
-        KeyCode::Char('+') => {
-            if matches!(state.focus, Focus::Sessions) && !state.sessions_expanded {
-                state.sessions_expanded = true;
+        KeyCode::Char('t') | KeyCode::Char('n') if matches!(state.focus, Focus::Sessions) => {
+            if let Some(VisibleItem::Session { session, .. }) =
+                state.visible_items.get(state.selected_index)
+            {
+                let is_tags = key.code == KeyCode::Char('t');
+                let key = crate::session::pin_key(session).to_string();
+                let input = if is_tags {
+                    let text = state
+                        .session_tags
+                        .get(&key)
+                        .map(|tags| tags.join(" "))
+                        .unwrap_or_default();
+                    LineInput::new(LineInputPurpose::Tags { key }, text)
+                } else {
+                    let text = state.session_notes.get(&key).cloned().unwrap_or_default();
+                    LineInput::new(LineInputPurpose::Note { key }, text)
+                };
+                state.line_input = Some(input);
             }
             None
         }
-        KeyCode::Char('_') => {
-            if matches!(state.focus, Focus::Sessions) && state.sessions_expanded {
-                state.sessions_expanded = false;
-            }
+        KeyCode::Char('N') if matches!(state.focus, Focus::Sessions) => {
+            let input = match state.visible_items.get(state.selected_index) {
+                Some(VisibleItem::Session { session, .. }) => {
+                    let text = session_title(state, session).to_string();
+                    let key = crate::session::pin_key(session).to_string();
+                    LineInput::new(LineInputPurpose::RenameSession { key }, text)
+                }
+                Some(VisibleItem::GroupHeader {
+                    group_key,
+                    tmux_session_name,
+                    display_name,
+                    ..
+                }) => LineInput::new(
+                    LineInputPurpose::RenameGroup {
+                        key: group_key.clone(),
+                        tmux_session_name: (state.group_by == GroupBy::TmuxSession)
+                            .then(|| tmux_session_name.clone()),
+                    },
+                    display_name.clone(),
+                ),
+                _ => return None,
+            };
+            state.line_input = Some(input);
             None
         }
-        KeyCode::Char('`') => {
-            state.flat_view = !state.flat_view;
+        KeyCode::Char('W') if matches!(state.focus, Focus::Sessions) => {
+            let session = match state.visible_items.get(state.selected_index) {
+                Some(VisibleItem::Session { session, .. }) => Some(session),
+                Some(VisibleItem::GroupHeader { group_key, .. }) => state
+                    .sessions
+                    .iter()
+                    .find(|s| group_key_of(state, s) == group_key && s.cwd.is_some()),
+                _ => return None,
+            };
+            let Some(session) = session else {
+                show_toast(state, "Working directory unknown".to_string());
+                return None;
+            };
+            let repo_root = session
+                .cwd
+                .as_deref()
+                .and_then(|cwd| crate::git::repo_root(std::path::Path::new(cwd)));
+            let Some(repo_root) = repo_root else {
+                show_toast(state, "Not in a git repository".to_string());
+                return None;
+            };
+            let purpose = LineInputPurpose::Worktree {
+                repo_root,
+                tmux_session_name: session.tmux_session_name.clone(),
+            };
+            state.line_input = Some(LineInput::new(purpose, String::new()));
+            None
+        }
+        KeyCode::Char('g') if matches!(state.focus, Focus::Sessions) => {
+            state.group_by = state.group_by.next(state.config.group_by_regex.is_some());
+            persist_ui_state(state);
             let old_items = std::mem::take(&mut state.visible_items);
             refresh_visible_items(state);
             state.selected_index =
                 resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
             update_selected_target(state, selected_pane_target);
+            if state.flat_view {
+                show_toast(
+                    state,
+                    format!("Group by {} (tree view)", state.group_by.label()),
+                );
+            }
             None
         }
-        _ => None,
-    }
-}
-
-fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent) -> Option<Action> {
-    if state.pending_confirm_target.is_some() || state.show_help {
-        return None;
-    }
-
-    if state.copy_mode.is_some() && matches!(mouse.kind, MouseEventKind::Down(_)) {
-        state.copy_mode = None;
-        state.preview_selection = None;
-    }
-
-    let in_preview = mouse.column >= state.preview_pane_area.x
-        && mouse.column < state.preview_pane_area.x + state.preview_pane_area.width
-        && mouse.row >= state.preview_pane_area.y
-        && mouse.row < state.preview_pane_area.y + state.preview_pane_area.height;
-
-    match mouse.kind {
-        MouseEventKind::Down(MouseButton::Left) => {
-            if !in_preview {
-                state.preview_selection = None;
-                return None;
-            }
+        KeyCode::Char('J') if matches!(state.focus, Focus::Sessions) => {
+            move_selected(state, 1, selected_pane_target);
+            None
+        }
+        KeyCode::Char('K') if matches!(state.focus, Focus::Sessions) => {
+            move_selected(state, -1, selected_pane_target);
+            None
+        }
+        KeyCode::Char('A') => {
+            if matches!(state.focus, Focus::Sessions) {
+                let group = match state.visible_items.get(state.selected_index) {
+                    Some(VisibleItem::Session { session, .. }) => {
+                        Some(group_key_of(state, session).to_string())
+                    }
+                    Some(VisibleItem::GroupHeader { group_key, .. }) => Some(group_key.clone()),
+                    _ => None,
+                };
+                if let Some(group) = group {
+                    toggle_group_marks(state, &group);
+                }
+            }
+            None
+        }
+        KeyCode::Char(' ') => {
+            if matches!(state.focus, Focus::Sessions) {
+                match state.visible_items.get(state.selected_index) {
+                    Some(VisibleItem::Session { session, .. }) => {
+                        let pane_id = session.pane_id.clone();
+                        if !state.marked_pane_ids.remove(&pane_id) {
+                            state.marked_pane_ids.insert(pane_id);
+                        }
+                    }
+                    Some(VisibleItem::GroupHeader { group_key, .. }) => {
+                        let group = group_key.clone();
+                        toggle_group_marks(state, &group);
+                    }
+                    _ => {}
+                }
+            }
+            None
+        }
+        KeyCode::Char('B') => {
+            if matches!(state.focus, Focus::Sessions) {
+                if let Some(composer) = new_broadcast_composer(state) {
+                    state.prompt_composer = Some(composer);
+                }
+            }
+            None
+        }
+        KeyCode::Char('I') => {
+            if matches!(state.focus, Focus::Sessions) {
+                if let Some(VisibleItem::Session { session, .. }) =
+                    state.visible_items.get(state.selected_index)
+                {
+                    if session.status == SessionStatus::Active {
+                        state.pending_confirm = Some(PendingConfirm::Interrupt {
+                            pane_id: session.pane_id.clone(),
+                            pane_target: session.pane_target.clone(),
+                            agent: session.agent,
+                            label: session_label(state, session),
+                        });
+                    } else {
+                        show_toast(state, "Agent is not busy".to_string());
+                    }
+                }
+            }
+            None
+        }
+        KeyCode::Char(':') => {
+            if matches!(state.focus, Focus::Sessions) {
+                let agent = get_selected_agent(state);
+                if let (Some(composer), Some(agent)) = (new_prompt_composer(state), agent) {
+                    state.command_palette = Some(CommandPalette {
+                        pane_id: composer.pane_id,
+                        pane_target: composer.pane_target,
+                        label: composer.label,
+                        commands: slash_commands::commands_for(agent, &state.config.slash_commands),
+                        query: String::new(),
+                        cursor: 0,
+                        selected: 0,
+                    });
+                }
+            }
+            None
+        }
+        KeyCode::Char('Q') => {
+            if matches!(state.focus, Focus::Sessions) {
+                if let Some(composer) = new_prompt_composer(state) {
+                    state.queue_editor = Some(QueueEditor {
+                        pane_id: composer.pane_id,
+                        pane_target: composer.pane_target,
+                        label: composer.label,
+                        selected: 0,
+                    });
+                }
+            }
+            None
+        }
+        KeyCode::Char('x') => {
+            if matches!(state.focus, Focus::Sessions) {
+                let bulk = bulk_pane_ids(state);
+                if !bulk.is_empty() {
+                    let targets = state
+                        .sessions
+                        .iter()
+                        .filter(|s| bulk.contains(&s.pane_id))
+                        .map(|s| (s.pane_target.clone(), session_label(state, s)))
+                        .collect();
+                    state.pending_confirm = Some(PendingConfirm::KillPanes {
+                        targets,
+                        typed: String::new(),
+                    });
+                    return None;
+                }
+                if let Some(VisibleItem::Session { session, .. }) = state
+                    .visible_items
+                    .get(state.selected_index)
+                    .cloned()
+                    .as_ref()
+                {
+                    state.pending_confirm =
+                        Some(PendingConfirm::KillPane(session.pane_target.clone()));
+                }
+            }
+            None
+        }
+        KeyCode::Char('/') => {
+            if matches!(state.focus, Focus::Sessions) {
+                state.session_filter_active = true;
+                state.session_filter_query.clear();
+                state.session_filter_cursor = 0;
+            } else if matches!(state.focus, Focus::Preview) && !state.preview_content.is_empty() {
+                state.preview_is_sticky_bottom = false;
+                state.preview_selection = None;
+                let visible_height = state.preview_area_height.saturating_sub(2);
+                let middle_row = (state.preview_scroll_offset + visible_height / 2)
+                    .min(state.preview_content_height.saturating_sub(1));
+                let mut copy = copy_mode::CopyModeState::new(middle_row, 0);
+                copy.search_active = true;
+                copy.search_direction = copy_mode::SearchDirection::Forward;
+                state.copy_mode = Some(copy);
+            }
+            None
+        }
+        KeyCode::Esc => {
+            if matches!(state.focus, Focus::Sessions) && state.visual_anchor.is_some() {
+                state.visual_anchor = None;
+            } else if matches!(state.focus, Focus::Sessions)
+                && state.session_filter_query.is_empty()
+                && !state.marked_pane_ids.is_empty()
+            {
+                state.marked_pane_ids.clear();
+            } else if matches!(state.focus, Focus::Sessions)
+                && !state.session_filter_query.is_empty()
+            {
+                state.session_filter_query.clear();
+                state.session_filter_cursor = 0;
+                refresh_visible_items(state);
+                state.selected_index = state
+                    .selected_index
+                    .min(state.visible_items.len().saturating_sub(1));
+                update_selected_target(state, selected_pane_target);
+            }
+            None
+        }
+        KeyCode::Char('?') => {
+            if matches!(state.focus, Focus::Preview) && !state.preview_content.is_empty() {
+                state.preview_is_sticky_bottom = false;
+                state.preview_selection = None;
+                let visible_height = state.preview_area_height.saturating_sub(2);
+                let middle_row = (state.preview_scroll_offset + visible_height / 2)
+                    .min(state.preview_content_height.saturating_sub(1));
+                let mut copy = copy_mode::CopyModeState::new(middle_row, 0);
+                copy.search_active = true;
+                copy.search_direction = copy_mode::SearchDirection::Backward;
+                state.copy_mode = Some(copy);
+            } else {
+                state.show_help = !state.show_help;
+            }
+            None
+        }
+        KeyCode::Char('D') => {
+            if state.diff_view.is_some() || state.diff_loading.is_some() {
+                close_diff(state, selected_pane_target);
+                return None;
+            }
+            match state.visible_items.get(state.selected_index) {
+                Some(VisibleItem::Session { session, .. }) => match session.cwd {
+                    Some(ref cwd) => Some(Action::ShowDiff {
+                        pane_id: session.pane_id.clone(),
+                        cwd: cwd.clone(),
+                    }),
+                    None => {
+                        show_toast(state, "Working directory unknown".to_string());
+                        None
+                    }
+                },
+                _ => None,
+            }
+        }
+        KeyCode::Char('[') | KeyCode::Char(']') if state.diff_view.is_some() => {
+            let forward = key.code == KeyCode::Char(']');
+            let target = state
+                .diff_view
+                .as_ref()
+                .and_then(|v| v.adjacent_file(state.preview_scroll_offset, forward));
+            if let Some(row) = target {
+                state.preview_scroll_offset = row;
+                state.preview_is_sticky_bottom = false;
+            }
+            None
+        }
+        KeyCode::Char('v') => {
+            if !state.preview_content.is_empty() {
+                state.focus = Focus::Preview;
+                state.sessions_expanded = false;
+                state.preview_is_sticky_bottom = false;
+                state.preview_selection = None;
+                let visible_height = state.preview_area_height.saturating_sub(2);
+                let middle_row = (state.preview_scroll_offset + visible_height / 2)
+                    .min(state.preview_content_height.saturating_sub(1));
+                state.copy_mode = Some(copy_mode::CopyModeState::new(middle_row, 0));
+            }
+            None
+        }
+        KeyCode::Char('y') => {
+            if matches!(state.focus, Focus::Preview) && !state.preview_content.is_empty() {
+                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                    let _ = clipboard.set_text(&state.preview_content);
+                    show_toast(state, "Copied!".to_string());
+                }
+            }
+            None
+        }
+        KeyCode::Char('+') => {
+            if matches!(state.focus, Focus::Sessions) && !state.sessions_expanded {
+                state.sessions_expanded = true;
+            }
+            None
+        }
+        KeyCode::Char('_') => {
+            if matches!(state.focus, Focus::Sessions) && state.sessions_expanded {
+                state.sessions_expanded = false;
+            }
+            None
+        }
+        KeyCode::Char('s') => {
+            if matches!(state.focus, Focus::Sessions) {
+                state.sort_mode = state.sort_mode.next();
+                persist_ui_state(state);
+                let old_items = std::mem::take(&mut state.visible_items);
+                refresh_visible_items(state);
+                state.selected_index =
+                    resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+                update_selected_target(state, selected_pane_target);
+            }
+            None
+        }
+        KeyCode::Char('`') => {
+            state.flat_view = !state.flat_view;
+            let old_items = std::mem::take(&mut state.visible_items);
+            refresh_visible_items(state);
+            state.selected_index =
+                resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+            update_selected_target(state, selected_pane_target);
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Moves the selected session (within its group in the tree view) or group
+/// one step up or down and switches to manual sort, starting from the order
+/// currently shown. Returns false when there is nothing to move past.
+fn move_selected(
+    state: &mut AppState,
+    delta: isize,
+    selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+) -> bool {
+    let shown_sessions: Vec<(String, String)> = state
+        .visible_items
+        .iter()
+        .filter_map(|item| match item {
+            VisibleItem::Session { session, .. } => Some((
+                crate::session::pin_key(session).to_string(),
+                group_key_of(state, session).to_string(),
+            )),
+            _ => None,
+        })
+        .collect();
+    let mut session_keys: Vec<String> = Vec::new();
+    for (key, _) in &shown_sessions {
+        if !session_keys.contains(key) {
+            session_keys.push(key.clone());
+        }
+    }
+    let mut group_names: Vec<String> = Vec::new();
+    for item in &state.visible_items {
+        if let VisibleItem::GroupHeader { group_key, .. } = item {
+            if !group_names.contains(group_key) {
+                group_names.push(group_key.clone());
+            }
+        }
+    }
+
+    let step = |order: &mut Vec<String>, scope: Vec<&String>, key: &str| -> bool {
+        let Some(pos) = scope.iter().position(|k| k.as_str() == key) else {
+            return false;
+        };
+        let Some(neighbour) = pos.checked_add_signed(delta).and_then(|i| scope.get(i)) else {
+            return false;
+        };
+        let a = order.iter().position(|k| k == key);
+        let b = order.iter().position(|k| k == *neighbour);
+        match (a, b) {
+            (Some(a), Some(b)) => {
+                order.swap(a, b);
+                true
+            }
+            _ => false,
+        }
+    };
+
+    let moved = match state.visible_items.get(state.selected_index) {
+        Some(VisibleItem::Session { session, .. }) => {
+            let key = crate::session::pin_key(session).to_string();
+            let own_group = group_key_of(state, session);
+            let scope: Vec<&String> = shown_sessions
+                .iter()
+                .filter(|(_, group)| state.flat_view || group == own_group)
+                .map(|(key, _)| key)
+                .collect();
+            let mut order = session_keys.clone();
+            step(&mut order, scope, &key).then_some((order, group_names.clone()))
+        }
+        Some(VisibleItem::GroupHeader { group_key, .. }) => {
+            let mut order = group_names.clone();
+            let scope: Vec<&String> = group_names.iter().collect();
+            step(&mut order, scope, group_key).then_some((session_keys.clone(), order))
+        }
+        _ => None,
+    };
+    let Some((sessions, groups)) = moved else {
+        return false;
+    };
+
+    state.manual_order.sessions =
+        crate::session::merge_order(sessions, &state.manual_order.sessions);
+    state.manual_order.groups = crate::session::merge_order(groups, &state.manual_order.groups);
+    state.sort_mode = SortMode::Manual;
+    persist_ui_state(state);
+    let old_items = std::mem::take(&mut state.visible_items);
+    refresh_visible_items(state);
+    state.selected_index =
+        resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+    update_selected_target(state, selected_pane_target);
+    true
+}
+
+fn handle_prompt_composer_key(state: &mut AppState, key: KeyEvent) -> Option<Action> {
+    let composer = state.prompt_composer.as_mut()?;
+    match key.code {
+        KeyCode::Esc => {
+            let composer = state.prompt_composer.take()?;
+            if let Some(index) = composer.editing {
+                reopen_queue_editor(state, composer, index);
+            }
+            None
+        }
+        KeyCode::Enter => {
+            let composer = state.prompt_composer.take()?;
+            submit_prompt(state, composer)
+        }
+        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            let composer = state.prompt_composer.take()?;
+            Some(Action::ComposeInEditor(composer))
+        }
+        _ => {
+            apply_text_input(&mut composer.text, &mut composer.cursor, key);
+            None
+        }
+    }
+}
+
+/// Composer for the selected session, or `None` (with a toast when useful)
+/// if it can't take a prompt right now.
+fn new_prompt_composer(state: &mut AppState) -> Option<PromptComposer> {
+    let Some(VisibleItem::Session { session, .. }) = state.visible_items.get(state.selected_index)
+    else {
+        return None;
+    };
+    if session.status == SessionStatus::Exited {
+        return None;
+    }
+    Some(PromptComposer {
+        pane_id: session.pane_id.clone(),
+        pane_target: session.pane_target.clone(),
+        label: session_label(state, session),
+        text: String::new(),
+        cursor: 0,
+        editing: None,
+        broadcast_pane_ids: Vec::new(),
+    })
+}
+
+fn session_label(state: &AppState, session: &AgentSession) -> String {
+    let group = state
+        .display_name_map
+        .get(&session.tmux_session_name)
+        .unwrap_or(&session.tmux_session_name);
+    format!("{} · {}", group, session_title(state, session))
+}
+
+/// Rows covered by the visual range, resolved from the anchor's pane so the
+/// range follows it through re-sorts. `None` once the anchor is gone.
+pub fn visual_range(state: &AppState) -> Option<std::ops::RangeInclusive<usize>> {
+    let anchor = state.visual_anchor.as_ref()?;
+    let anchor_index = state.visible_items.iter().position(
+        |item| matches!(item, VisibleItem::Session { session, .. } if session.pane_id == *anchor),
+    )?;
+    Some(anchor_index.min(state.selected_index)..=anchor_index.max(state.selected_index))
+}
+
+/// Pane ids the bulk actions apply to: marked sessions plus the rows covered
+/// by an active visual range.
+fn bulk_pane_ids(state: &AppState) -> HashSet<String> {
+    let mut ids = state.marked_pane_ids.clone();
+    if let Some(range) = visual_range(state) {
+        for item in state
+            .visible_items
+            .iter()
+            .take(range.end() + 1)
+            .skip(*range.start())
+        {
+            if let VisibleItem::Session { session, .. } = item {
+                ids.insert(session.pane_id.clone());
+            }
+        }
+    }
+    ids
+}
+
+/// Marks every session in the tmux session group, or unmarks them all if the
+/// group is already fully marked.
+fn toggle_group_marks(state: &mut AppState, group: &str) {
+    let members: Vec<String> = state
+        .sessions
+        .iter()
+        .filter(|s| group_key_of(state, s) == group)
+        .map(|s| s.pane_id.clone())
+        .collect();
+    if members.iter().all(|id| state.marked_pane_ids.contains(id)) {
+        for id in &members {
+            state.marked_pane_ids.remove(id);
+        }
+    } else {
+        state.marked_pane_ids.extend(members);
+    }
+}
+
+/// Composer that broadcasts to every marked session still in the list.
+fn new_broadcast_composer(state: &mut AppState) -> Option<PromptComposer> {
+    let bulk = bulk_pane_ids(state);
+    let mut pane_ids: Vec<String> = state
+        .sessions
+        .iter()
+        .filter(|s| bulk.contains(&s.pane_id))
+        .map(|s| s.pane_id.clone())
+        .collect();
+    // A visual range becomes regular marks so the broadcast can be repeated
+    state.marked_pane_ids.extend(pane_ids.iter().cloned());
+    state.visual_anchor = None;
+    if pane_ids.is_empty() {
+        show_toast(state, "Mark sessions with Space first".to_string());
+        return None;
+    }
+    pane_ids.sort();
+    Some(PromptComposer {
+        pane_id: String::new(),
+        pane_target: String::new(),
+        label: format!("{} marked sessions", pane_ids.len()),
+        text: String::new(),
+        cursor: 0,
+        editing: None,
+        broadcast_pane_ids: pane_ids,
+    })
+}
+
+/// Sends the composed prompt, queues it while the agent is busy, or stores it
+/// back into the queue when editing a queued entry.
+fn submit_prompt(state: &mut AppState, composer: PromptComposer) -> Option<Action> {
+    if let Some(index) = composer.editing {
+        if !composer.text.trim().is_empty() {
+            let queue = state
+                .prompt_queues
+                .entry(composer.pane_id.clone())
+                .or_default();
+            if index < queue.len() {
+                queue[index] = composer.text.clone();
+            } else {
+                queue.push(composer.text.clone());
+            }
+            state.paused_queues.remove(&composer.pane_id);
+            persist_state(state);
+            refresh_visible_items(state);
+        }
+        reopen_queue_editor(state, composer, index);
+        return None;
+    }
+    if composer.text.trim().is_empty() {
+        return None;
+    }
+    if !composer.broadcast_pane_ids.is_empty() {
+        return Some(Action::Broadcast {
+            pane_ids: composer.broadcast_pane_ids,
+            text: composer.text,
+        });
+    }
+    if session_is_busy(state, &composer.pane_id) {
+        enqueue_prompt(state, &composer.pane_id, composer.text);
+        return None;
+    }
+    Some(Action::SendPrompt {
+        pane_id: composer.pane_id,
+        pane_target: composer.pane_target,
+        text: composer.text,
+    })
+}
+
+fn enqueue_prompt(state: &mut AppState, pane_id: &str, text: String) {
+    let queue = state.prompt_queues.entry(pane_id.to_string()).or_default();
+    queue.push(text);
+    let pending = queue.len();
+    state.paused_queues.remove(pane_id);
+    persist_state(state);
+    refresh_visible_items(state);
+    show_toast(state, format!("Queued ({} pending)", pending));
+}
+
+fn reopen_queue_editor(state: &mut AppState, composer: PromptComposer, index: usize) {
+    let len = state
+        .prompt_queues
+        .get(&composer.pane_id)
+        .map_or(0, Vec::len);
+    state.queue_editor = Some(QueueEditor {
+        pane_id: composer.pane_id,
+        pane_target: composer.pane_target,
+        label: composer.label,
+        selected: index.min(len.saturating_sub(1)),
+    });
+}
+
+/// Sends the next queued prompt to an idle session. Returns `false` (leaving
+/// the session to be marked unread) when nothing is queued, the agent is
+/// waiting on a question that needs a human answer, or the previous queued
+/// prompt has not been picked up yet.
+fn deliver_queued_prompt(
+    state: &mut AppState,
+    session: &AgentSession,
+    prompt_states: &HashMap<String, PromptState>,
+    tx: &mpsc::UnboundedSender<Message>,
+) -> bool {
+    let now = now_millis();
+    if session.status != SessionStatus::Idle
+        || prompt_states
+            .get(&session.pane_id)
+            .is_some_and(|p| *p != PromptState::None)
+        || state
+            .queued_prompt_sent_at
+            .get(&session.pane_id)
+            .is_some_and(|sent_at| now.saturating_sub(*sent_at) < QUEUED_PROMPT_RETRY_MS)
+        || state.paused_queues.contains(&session.pane_id)
+    {
+        return false;
+    }
+    let Some(queue) = state.prompt_queues.get_mut(&session.pane_id) else {
+        return false;
+    };
+    if queue.is_empty() {
+        return false;
+    }
+    let text = queue.remove(0);
+    if queue.is_empty() {
+        state.prompt_queues.remove(&session.pane_id);
+    }
+    state
+        .queued_prompt_sent_at
+        .insert(session.pane_id.clone(), now);
+    let pane_id = session.pane_id.clone();
+    let pane_target = session.pane_target.clone();
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        if crate::tmux::send_prompt(&pane_target, &text).await.is_err() {
+            let _ = tx.send(Message::QueuedPromptFailed { pane_id, text });
+        }
+    });
+    show_toast(state, "Sent queued prompt".to_string());
+    true
+}
+
+fn handle_command_palette_key(state: &mut AppState, key: KeyEvent) -> Option<Action> {
+    let palette = state.command_palette.as_mut()?;
+    match key.code {
+        KeyCode::Esc => {
+            state.command_palette = None;
+            None
+        }
+        KeyCode::Down => {
+            palette.selected =
+                (palette.selected + 1).min(palette.matches().len().saturating_sub(1));
+            None
+        }
+        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            palette.selected =
+                (palette.selected + 1).min(palette.matches().len().saturating_sub(1));
+            None
+        }
+        KeyCode::Up => {
+            palette.selected = palette.selected.saturating_sub(1);
+            None
+        }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            palette.selected = palette.selected.saturating_sub(1);
+            None
+        }
+        KeyCode::Enter | KeyCode::Tab => {
+            let index = *palette.matches().get(palette.selected)?;
+            let palette = state.command_palette.take()?;
+            let command = &palette.commands[index];
+            // Commands needing an argument (and Tab) go through the composer first
+            let prefill = match command.argument_prefix() {
+                Some(prefix) => Some(prefix.to_string()),
+                None if key.code == KeyCode::Tab => Some(format!("{} ", command.name)),
+                None => None,
+            };
+            match prefill {
+                Some(text) => {
+                    state.prompt_composer = Some(PromptComposer {
+                        pane_id: palette.pane_id,
+                        pane_target: palette.pane_target,
+                        label: palette.label,
+                        cursor: text.chars().count(),
+                        text,
+                        editing: None,
+                        broadcast_pane_ids: Vec::new(),
+                    });
+                    None
+                }
+                None => Some(Action::SendPrompt {
+                    pane_id: palette.pane_id,
+                    pane_target: palette.pane_target,
+                    text: command.name.clone(),
+                }),
+            }
+        }
+        _ => {
+            if apply_text_input(&mut palette.query, &mut palette.cursor, key) {
+                palette.selected = 0;
+            }
+            None
+        }
+    }
+}
+
+/// Offers the launch profiles for the group of `item` in the directories of
+/// its sessions. With a single choice the window is opened right away.
+fn open_launch_picker(
+    state: &mut AppState,
+    item: &VisibleItem,
+    tmux_session_name: String,
+    cwd_target: String,
+) -> Option<Action> {
+    let (group_key, first_cwd) = match item {
+        VisibleItem::Session { session, .. } => (
+            group_key_of(state, session).to_string(),
+            session.cwd.clone(),
+        ),
+        VisibleItem::GroupHeader { group_key, .. } => (group_key.clone(), None),
+        _ => return None,
+    };
+    let mut dirs: Vec<String> = Vec::new();
+    // A session with an unknown cwd gets no directories, so the window opens
+    // in its pane's current one
+    if first_cwd.is_some() || matches!(item, VisibleItem::GroupHeader { .. }) {
+        dirs.extend(first_cwd);
+        for session in &state.sessions {
+            if let Some(ref cwd) = session.cwd {
+                if group_key_of(state, session) == group_key && !dirs.contains(cwd) {
+                    dirs.push(cwd.clone());
+                }
+            }
+        }
+    }
+    let config = crate::config::load_config(state.config.exit_on_switch);
+    let last_used = state.last_launch_profiles.get(&group_key);
+    let profiles = launch::profiles(&config, last_used.map(String::as_str));
+    let entries = launch::entries(&profiles, &dirs);
+    if let [entry] = entries.as_slice() {
+        return Some(Action::CreateSession {
+            tmux_session_name,
+            cwd_target,
+            cwd: entry.cwd.clone(),
+            profile: profiles[entry.profile].clone(),
+        });
+    }
+    state.launch_picker = Some(LaunchPicker {
+        group_key,
+        tmux_session_name,
+        cwd_target,
+        summaries: profiles.iter().map(|p| p.summary(&config)).collect(),
+        profiles,
+        entries,
+        query: String::new(),
+        cursor: 0,
+        selected: 0,
+    });
+    None
+}
+
+fn handle_launch_picker_key(state: &mut AppState, key: KeyEvent) -> Option<Action> {
+    let picker = state.launch_picker.as_mut()?;
+    match key.code {
+        KeyCode::Esc => {
+            state.launch_picker = None;
+            None
+        }
+        KeyCode::Down => {
+            picker.selected = (picker.selected + 1).min(picker.matches().len().saturating_sub(1));
+            None
+        }
+        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            picker.selected = (picker.selected + 1).min(picker.matches().len().saturating_sub(1));
+            None
+        }
+        KeyCode::Up => {
+            picker.selected = picker.selected.saturating_sub(1);
+            None
+        }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            picker.selected = picker.selected.saturating_sub(1);
+            None
+        }
+        KeyCode::Enter => {
+            let index = *picker.matches().get(picker.selected)?;
+            let picker = state.launch_picker.take()?;
+            let entry = &picker.entries[index];
+            let profile = picker.profiles[entry.profile].clone();
+            state
+                .last_launch_profiles
+                .insert(picker.group_key, profile.name.clone());
+            persist_state(state);
+            Some(Action::CreateSession {
+                tmux_session_name: picker.tmux_session_name,
+                cwd_target: picker.cwd_target,
+                cwd: entry.cwd.clone(),
+                profile,
+            })
+        }
+        _ => {
+            if apply_text_input(&mut picker.query, &mut picker.cursor, key) {
+                picker.selected = 0;
+            }
+            None
+        }
+    }
+}
+
+fn handle_queue_editor_key(state: &mut AppState, key: KeyEvent) -> Option<Action> {
+    let editor = state.queue_editor.as_mut()?;
+    let queue = state
+        .prompt_queues
+        .entry(editor.pane_id.clone())
+        .or_default();
+    let mut changed = false;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('Q') | KeyCode::Char('q') => {
+            state.queue_editor = None;
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            editor.selected = (editor.selected + 1).min(queue.len().saturating_sub(1));
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            editor.selected = editor.selected.saturating_sub(1);
+        }
+        KeyCode::Char('J') if editor.selected + 1 < queue.len() => {
+            queue.swap(editor.selected, editor.selected + 1);
+            editor.selected += 1;
+            changed = true;
+        }
+        KeyCode::Char('K') if editor.selected > 0 && editor.selected < queue.len() => {
+            queue.swap(editor.selected, editor.selected - 1);
+            editor.selected -= 1;
+            changed = true;
+        }
+        KeyCode::Char('d') | KeyCode::Char('x') if editor.selected < queue.len() => {
+            queue.remove(editor.selected);
+            editor.selected = editor.selected.min(queue.len().saturating_sub(1));
+            changed = true;
+        }
+        KeyCode::Char('e') | KeyCode::Enter if editor.selected < queue.len() => {
+            let text = queue[editor.selected].clone();
+            let editor = state.queue_editor.take()?;
+            state.prompt_composer = Some(PromptComposer {
+                pane_id: editor.pane_id,
+                pane_target: editor.pane_target,
+                label: editor.label,
+                cursor: text.chars().count(),
+                text,
+                editing: Some(editor.selected),
+                broadcast_pane_ids: Vec::new(),
+            });
+        }
+        KeyCode::Char('a') | KeyCode::Char('i') => {
+            let index = queue.len();
+            let editor = state.queue_editor.take()?;
+            state.prompt_composer = Some(PromptComposer {
+                pane_id: editor.pane_id,
+                pane_target: editor.pane_target,
+                label: editor.label,
+                text: String::new(),
+                cursor: 0,
+                editing: Some(index),
+                broadcast_pane_ids: Vec::new(),
+            });
+        }
+        _ => {}
+    }
+    state.prompt_queues.retain(|_, q| !q.is_empty());
+    if changed {
+        if let Some(editor) = &state.queue_editor {
+            state.paused_queues.remove(&editor.pane_id);
+        }
+        persist_state(state);
+        refresh_visible_items(state);
+    }
+    None
+}
+
+fn session_is_busy(state: &AppState, pane_id: &str) -> bool {
+    state
+        .sessions
+        .iter()
+        .any(|s| s.pane_id == pane_id && s.status == SessionStatus::Active)
+}
+
+/// Leaves the TUI, opens `initial` in the user's editor and returns the saved
+/// text with trailing whitespace trimmed, or `None` if it ended up empty.
+fn edit_in_external_editor(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    initial: &str,
+) -> Result<Option<String>> {
+    use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+    use crossterm::terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    };
+
+    let path = std::env::temp_dir().join(format!("agent-dash-{}-prompt.md", std::process::id()));
+    std::fs::write(&path, initial)?;
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    disable_raw_mode()?;
+    crossterm::execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    // Run through the shell so editors configured with arguments ("code -w") work
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status();
+    enable_raw_mode()?;
+    crossterm::execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+
+    let text = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+    if !status?.success() {
+        return Ok(None);
+    }
+    let text = text?.trim_end().to_string();
+    Ok((!text.trim().is_empty()).then_some(text))
+}
+
+fn handle_mouse_event(
+    state: &mut AppState,
+    mouse: MouseEvent,
+    selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+) -> Option<Action> {
+    if state.pending_confirm.is_some()
+        || state.show_help
+        || state.prompt_composer.is_some()
+        || state.queue_editor.is_some()
+        || state.command_palette.is_some()
+        || state.launch_picker.is_some()
+        || state.line_input.is_some()
+    {
+        return None;
+    }
+
+    if state.copy_mode.is_some() && matches!(mouse.kind, MouseEventKind::Down(_)) {
+        state.copy_mode = None;
+        state.preview_selection = None;
+    }
+
+    let in_preview = mouse.column >= state.preview_pane_area.x
+        && mouse.column < state.preview_pane_area.x + state.preview_pane_area.width
+        && mouse.row >= state.preview_pane_area.y
+        && mouse.row < state.preview_pane_area.y + state.preview_pane_area.height;
+
+    let list = state.session_list_rows;
+    let in_list = mouse.column >= list.x
+        && mouse.column < list.x + list.width
+        && mouse.row >= list.y
+        && mouse.row < list.y + list.height;
+    let list_index = state.session_list_offset + mouse.row.saturating_sub(list.y) as usize;
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) if in_list => {
+            state.preview_selection = None;
+            if list_index < state.visible_items.len() {
+                state.focus = Focus::Sessions;
+                if state.selected_index != list_index {
+                    state.selected_index = list_index;
+                    state.preview_content.clear();
+                    update_selected_target(state, selected_pane_target);
+                }
+                state.drag_index = Some(list_index);
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) if state.drag_index.is_some() => {
+            let target = if mouse.row < list.y {
+                state.session_list_offset.saturating_sub(1)
+            } else {
+                list_index.min(state.visible_items.len().saturating_sub(1))
+            };
+            // Move one step at a time; stops at the edge of the item's group
+            for _ in 0..state.visible_items.len() {
+                let delta = match target.cmp(&state.selected_index) {
+                    std::cmp::Ordering::Less => -1,
+                    std::cmp::Ordering::Greater => 1,
+                    std::cmp::Ordering::Equal => break,
+                };
+                let before = state.selected_index;
+                if !move_selected(state, delta, selected_pane_target)
+                    || state.selected_index == before
+                {
+                    break;
+                }
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) if state.drag_index.is_some() => {
+            state.drag_index = None;
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if !in_preview {
+                state.preview_selection = None;
+                return None;
+            }
             if let Some(pos) = selection::mouse_to_content_position(
                 mouse.column,
                 mouse.row,
@@ -1293,10 +3496,7 @@ fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent) -> Option<Action>
                     if !selected.is_empty() {
                         if let Ok(mut clipboard) = arboard::Clipboard::new() {
                             let _ = clipboard.set_text(&selected);
-                            state.toast_message = Some("Copied to clipboard!".to_string());
-                            state.toast_deadline = Some(
-                                std::time::Instant::now() + std::time::Duration::from_millis(1500),
-                            );
+                            show_toast(state, "Copied to clipboard!".to_string());
                         }
                     }
                 }
@@ -1324,31 +3524,47 @@ fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent) -> Option<Action>
 }
 
 fn refresh_visible_items(state: &mut AppState) {
+    let group_labels = update_group_keys(state);
     let parsed = crate::filter_query::parse_filter_query(&state.session_filter_query);
     let include_hidden = parsed.include_hidden;
+    let sessions: Vec<AgentSession> = state
+        .sessions
+        .iter()
+        .filter(|s| parsed.matches(&session_facts(state, s)))
+        .cloned()
+        .collect();
 
     if state.flat_view {
         state.visible_items = build_flat_visible_items(
-            &state.sessions,
+            &sessions,
             &state.unread_pane_ids,
             &state.unread_order,
+            &state.status_changed_at,
+            state.sort_mode,
             &state.prompt_states,
             &state.display_name_map,
             &state.hidden_pane_ids,
             &state.hidden_groups,
             state.hidden_section_collapsed,
             include_hidden,
+            &state.pinned_sessions,
+            &state.pinned_groups,
+            &state.manual_order,
+            &state.group_keys,
+            &state.session_names,
         );
     } else {
-        let groups = group_sessions_by_name(&state.sessions);
+        let groups = group_sessions(&sessions, &state.group_keys);
         state.visible_items = build_visible_items(
             &groups,
             &state.collapsed_groups,
             &state.collapsed_hidden_groups,
             &state.unread_pane_ids,
             &state.unread_order,
+            &state.status_changed_at,
+            state.sort_mode,
             &state.prompt_states,
-            &state.display_name_map,
+            &group_labels,
             &state.hidden_pane_ids,
             &state.hidden_groups,
             state.hidden_section_collapsed,
@@ -1357,10 +3573,22 @@ fn refresh_visible_items(state: &mut AppState) {
             state.config.group_name_separator.as_deref(),
             &state.collapsed_subgroups,
             &state.collapsed_hidden_subgroups,
+            &state.pinned_sessions,
+            &state.pinned_groups,
+            &state.manual_order,
+            &state.group_keys,
+            &state.session_names,
         );
     }
 
-    if !state.session_filter_query.is_empty() {
+    if state.session_filter_query.is_empty() {
+        crate::session::append_recent_items(
+            &mut state.visible_items,
+            &state.history,
+            &state.sessions,
+            state.recent_section_collapsed,
+        );
+    } else {
         use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
         use nucleo_matcher::{Matcher, Utf32Str};
 
@@ -1377,11 +3605,7 @@ fn refresh_visible_items(state: &mut AppState) {
                         display_name,
                         ..
                     } => {
-                        let haystack = if session.title.is_empty() {
-                            display_name.clone()
-                        } else {
-                            format!("{}/{}", display_name, session.title)
-                        };
+                        let haystack = filter_haystack(display_name, session_title(state, session));
                         let mut buf = Vec::new();
                         let score =
                             pattern.score(Utf32Str::new(&haystack, &mut buf), &mut matcher)?;
@@ -1395,6 +3619,354 @@ fn refresh_visible_items(state: &mut AppState) {
             state.visible_items = scored.into_iter().map(|(_, item)| item).collect();
         }
     }
+
+    let mut counts = vec![unread_count_for_query(state, "")];
+    for view in state.all_views() {
+        counts.push(unread_count_for_query(state, &view.query));
+    }
+    state.view_unread_counts = counts;
+}
+
+/// Moves pins, tags, notes and names stored under a pane id over to the session id
+/// once enrichment finds one, and drops pane-id entries for panes that are
+/// gone. Entries keyed by session id are kept so they return with a resumed
+/// session.
+fn rekey_session_annotations(
+    state: &mut AppState,
+    sessions: &[AgentSession],
+    current_pane_ids: &HashSet<String>,
+) {
+    let is_stale = |key: &String| key.starts_with('%') && !current_pane_ids.contains(key);
+    for session in sessions {
+        let Some(ref session_id) = session.session_id else {
+            continue;
+        };
+        if state.pinned_sessions.remove(&session.pane_id) {
+            state.pinned_sessions.insert(session_id.clone());
+        }
+        if let Some(tags) = state.session_tags.remove(&session.pane_id) {
+            state.session_tags.insert(session_id.clone(), tags);
+        }
+        if let Some(note) = state.session_notes.remove(&session.pane_id) {
+            state.session_notes.insert(session_id.clone(), note);
+        }
+        if let Some(name) = state.session_names.remove(&session.pane_id) {
+            state.session_names.insert(session_id.clone(), name);
+        }
+    }
+    state.pinned_sessions.retain(|key| !is_stale(key));
+    state.session_tags.retain(|key, _| !is_stale(key));
+    state.session_notes.retain(|key, _| !is_stale(key));
+    state.session_names.retain(|key, _| !is_stale(key));
+}
+
+/// Carries collapse, hide, pin, order and alias state over to a tmux session
+/// that was renamed from `from` to `to`.
+fn rename_group_key(state: &mut AppState, from: &str, to: &str) {
+    for set in [
+        &mut state.collapsed_groups,
+        &mut state.collapsed_hidden_groups,
+        &mut state.group_hidden_collapsed,
+        &mut state.hidden_groups,
+        &mut state.pinned_groups,
+    ] {
+        if set.remove(from) {
+            set.insert(to.to_string());
+        }
+    }
+    for key in state.manual_order.groups.iter_mut().filter(|k| *k == from) {
+        *key = to.to_string();
+    }
+    state.group_aliases.remove(from);
+    if let Some(profile) = state.last_launch_profiles.remove(from) {
+        state.last_launch_profiles.insert(to.to_string(), profile);
+    }
+}
+
+/// Group key of `session` under the current grouping.
+fn group_key_of<'a>(state: &'a AppState, session: &'a AgentSession) -> &'a str {
+    group_key(&state.group_keys, session)
+}
+
+/// Recomputes `group_keys` for the current grouping and returns the header
+/// labels to use: the display-name map for tmux sessions, or the grouping's
+/// own labels. Repository roots are looked up once per cwd.
+fn update_group_keys(state: &mut AppState) -> HashMap<String, String> {
+    state.group_keys.clear();
+    if state.group_by == GroupBy::TmuxSession {
+        return state.display_name_map.clone();
+    }
+    let mut labels = HashMap::new();
+    let repo_roots = &mut state.repo_roots;
+    for session in &state.sessions {
+        let (key, label) = crate::grouping::group_key(
+            session,
+            state.group_by,
+            state.config.group_by_regex.as_ref(),
+            |cwd| {
+                repo_roots
+                    .entry(cwd.to_string())
+                    .or_insert_with(|| {
+                        crate::git::repo_root(std::path::Path::new(cwd))
+                            .map(|p| p.to_string_lossy().into_owned())
+                    })
+                    .clone()
+            },
+        );
+        let label = state.group_aliases.get(&key).cloned().unwrap_or(label);
+        labels.insert(key.clone(), label);
+        state.group_keys.insert(session.pane_id.clone(), key);
+    }
+    labels
+}
+
+fn session_facts<'a>(state: &'a AppState, session: &'a AgentSession) -> SessionFacts<'a> {
+    let mut group_names = vec![session.tmux_session_name.as_str()];
+    group_names.extend(
+        state
+            .display_name_map
+            .get(&session.tmux_session_name)
+            .map(String::as_str),
+    );
+    SessionFacts {
+        status: &session.status,
+        is_unread: state.unread_pane_ids.contains(&session.pane_id),
+        is_stuck: state.stuck_pane_ids.contains(&session.pane_id),
+        prompt_state: state
+            .prompt_states
+            .get(&session.pane_id)
+            .unwrap_or(&PromptState::None),
+        agent: session.agent,
+        model: session.model.as_deref(),
+        cwd: session.cwd.as_deref(),
+        group_names,
+        role: session.agent_role.as_deref(),
+        tags: state
+            .session_tags
+            .get(crate::session::pin_key(session))
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+    }
+}
+
+/// Pane title of `session`, or the name it was given with `N`.
+pub fn session_title<'a>(state: &'a AppState, session: &'a AgentSession) -> &'a str {
+    state
+        .session_names
+        .get(crate::session::pin_key(session))
+        .map_or(session.title.as_str(), String::as_str)
+}
+
+/// Text the fuzzy part of the session filter is matched against.
+fn filter_haystack(display_name: &str, title: &str) -> String {
+    if title.is_empty() {
+        display_name.to_string()
+    } else {
+        format!("{}/{}", display_name, title)
+    }
+}
+
+/// Number of unread sessions a tab with `query` would list.
+fn unread_count_for_query(state: &AppState, query: &str) -> usize {
+    use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+    use nucleo_matcher::{Matcher, Utf32Str};
+
+    let parsed = crate::filter_query::parse_filter_query(query);
+    let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
+    let pattern = Pattern::parse(&parsed.text, CaseMatching::Smart, Normalization::Smart);
+    state
+        .sessions
+        .iter()
+        .filter(|s| state.unread_pane_ids.contains(&s.pane_id))
+        .filter(|s| {
+            parsed.include_hidden
+                || !(state.hidden_pane_ids.contains(&s.pane_id)
+                    || state.hidden_groups.contains(group_key_of(state, s)))
+        })
+        .filter(|s| parsed.matches(&session_facts(state, s)))
+        .filter(|s| {
+            if parsed.text.is_empty() {
+                return true;
+            }
+            let display_name = state
+                .display_name_map
+                .get(&s.tmux_session_name)
+                .unwrap_or(&s.tmux_session_name);
+            let haystack = filter_haystack(display_name, session_title(state, s));
+            let mut buf = Vec::new();
+            pattern
+                .score(Utf32Str::new(&haystack, &mut buf), &mut matcher)
+                .is_some()
+        })
+        .count()
+}
+
+/// Switches to a view tab, or back to "All" with `None`.
+fn select_view(
+    state: &mut AppState,
+    index: Option<usize>,
+    selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+) {
+    state.active_view = index;
+    match index.and_then(|i| state.all_views().get(i).cloned()) {
+        Some(view) => {
+            state.session_filter_query = view.query;
+            if let Some(flat) = view.flat {
+                state.flat_view = flat;
+            }
+            if let Some(sort) = view.sort {
+                state.sort_mode = sort;
+                persist_ui_state(state);
+            }
+        }
+        None => {
+            state.active_view = None;
+            state.session_filter_query.clear();
+        }
+    }
+    state.session_filter_active = false;
+    state.session_filter_cursor = state.session_filter_query.chars().count();
+    let old_items = std::mem::take(&mut state.visible_items);
+    refresh_visible_items(state);
+    state.selected_index =
+        resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+    update_selected_target(state, selected_pane_target);
+}
+
+fn handle_line_input_key(
+    state: &mut AppState,
+    key: KeyEvent,
+    selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+) -> Option<Action> {
+    let input = state.line_input.as_mut()?;
+    match key.code {
+        KeyCode::Esc => state.line_input = None,
+        KeyCode::Enter => {
+            let input = state.line_input.take()?;
+            let text = input.text.trim().to_string();
+            let mut action = None;
+            match input.purpose {
+                LineInputPurpose::SaveView { editing } => {
+                    if !save_view(state, editing, text) {
+                        return None;
+                    }
+                }
+                LineInputPurpose::Tags { key } => {
+                    let mut tags: Vec<String> = Vec::new();
+                    for tag in text.split([' ', ',']).filter(|t| !t.is_empty()) {
+                        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                            tags.push(tag.to_string());
+                        }
+                    }
+                    if tags.is_empty() {
+                        state.session_tags.remove(&key);
+                    } else {
+                        state.session_tags.insert(key, tags);
+                    }
+                }
+                LineInputPurpose::Note { key } => {
+                    if text.is_empty() {
+                        state.session_notes.remove(&key);
+                    } else {
+                        state.session_notes.insert(key, text);
+                    }
+                }
+                LineInputPurpose::RenameSession { key } => {
+                    if text.is_empty() {
+                        state.session_names.remove(&key);
+                    } else {
+                        state.session_names.insert(key, text);
+                    }
+                }
+                LineInputPurpose::RenameGroup {
+                    key,
+                    tmux_session_name,
+                } => match tmux_session_name {
+                    Some(name) if state.config.rename_tmux_session && !text.is_empty() => {
+                        if text != name {
+                            action = Some(Action::RenameTmuxSession {
+                                from: name,
+                                to: text,
+                            });
+                        }
+                    }
+                    tmux_session_name => {
+                        // Show a tmux session's alias now instead of after the
+                        // next poll; a cleared one waits for the formatter
+                        if text.is_empty() {
+                            state.group_aliases.remove(&key);
+                        } else {
+                            if let Some(name) = tmux_session_name {
+                                state.display_name_map.insert(name, text.clone());
+                            }
+                            state.group_aliases.insert(key, text);
+                        }
+                    }
+                },
+                LineInputPurpose::Worktree {
+                    repo_root,
+                    tmux_session_name,
+                } => {
+                    if text.is_empty() {
+                        return None;
+                    }
+                    action = Some(Action::CreateWorktree {
+                        repo_root,
+                        branch: text,
+                        tmux_session_name,
+                    });
+                }
+            }
+            persist_state(state);
+            let old_items = std::mem::take(&mut state.visible_items);
+            refresh_visible_items(state);
+            state.selected_index =
+                resolve_selected_index(&state.visible_items, &old_items, state.selected_index);
+            update_selected_target(state, selected_pane_target);
+            return action;
+        }
+        _ => {
+            apply_text_input(&mut input.text, &mut input.cursor, key);
+        }
+    }
+    None
+}
+
+/// Saves the current filter and layout as a view named `name`, replacing
+/// view `editing` (or one with the same name). An empty name deletes the
+/// edited view. Returns false when there was nothing to do.
+fn save_view(state: &mut AppState, editing: Option<usize>, name: String) -> bool {
+    let config_count = state.config.views.len();
+    match (editing, name.is_empty()) {
+        (Some(index), true) => {
+            let removed = state.saved_views.remove(index);
+            state.active_view = None;
+            show_toast(state, format!("Deleted view {}", removed.name));
+        }
+        (_, true) => return false,
+        (editing, false) => {
+            let view = SavedView {
+                name: name.clone(),
+                query: state.session_filter_query.clone(),
+                flat: Some(state.flat_view),
+                sort: Some(state.sort_mode),
+            };
+            let index =
+                match editing.or_else(|| state.saved_views.iter().position(|v| v.name == name)) {
+                    Some(index) => {
+                        state.saved_views[index] = view;
+                        index
+                    }
+                    None => {
+                        state.saved_views.push(view);
+                        state.saved_views.len() - 1
+                    }
+                };
+            state.active_view = Some(config_count + index);
+            show_toast(state, format!("Saved view {}", name));
+        }
+    }
+    true
 }
 
 fn get_selected_pane_target(state: &AppState) -> Option<String> {
@@ -1429,6 +4001,45 @@ fn forward_scroll_target(state: &AppState, col: u16, row: u16) -> Option<(String
     Some((target, col, row))
 }
 
+/// Leaves the diff view and has the preview task capture the pane again.
+fn close_diff(state: &mut AppState, selected_pane_target: &watch::Sender<Option<PreviewTarget>>) {
+    if let Some((_, task)) = state.diff_loading.take() {
+        task.abort();
+    }
+    if state.diff_view.take().is_some() {
+        state.copy_mode = None;
+        state.preview_selection = None;
+        state.preview_content.clear();
+        state.preview_is_sticky_bottom = true;
+        update_selected_target(state, selected_pane_target);
+    }
+}
+
+/// Keeps the diff view on the selected session: reloads it for a newly
+/// selected session, or closes it when no session is selected.
+fn diff_follow_selection(
+    state: &mut AppState,
+    selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
+) -> Option<Action> {
+    let view_pane = match state.diff_loading {
+        Some((ref loading, _)) => loading.clone(),
+        None => state.diff_view.as_ref()?.pane_id.clone(),
+    };
+    match state.visible_items.get(state.selected_index) {
+        Some(VisibleItem::Session { session, .. }) if session.pane_id == view_pane => None,
+        Some(VisibleItem::Session { session, .. }) if session.cwd.is_some() => {
+            Some(Action::ShowDiff {
+                pane_id: session.pane_id.clone(),
+                cwd: session.cwd.clone()?,
+            })
+        }
+        _ => {
+            close_diff(state, selected_pane_target);
+            None
+        }
+    }
+}
+
 fn update_selected_target(
     state: &AppState,
     selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
@@ -1473,6 +4084,9 @@ fn persist_ui_state(state: &AppState) {
             collapsed_hidden_groups: &state.collapsed_hidden_groups,
             hidden_section_collapsed: state.hidden_section_collapsed,
             group_hidden_collapsed: &state.group_hidden_collapsed,
+            recent_section_collapsed: state.recent_section_collapsed,
+            sort_mode: state.sort_mode,
+            group_by: state.group_by,
         }),
     );
 }
@@ -1481,15 +4095,33 @@ fn save_with(state: &AppState, instance: Option<state::InstanceSaveArgs<'_>>) {
     state::save_state(state::SaveArgs {
         unread_pane_ids: &state.unread_pane_ids,
         prev_status_map: &state.prev_status_map,
+        status_changed_at: &state.status_changed_at,
         unread_order: &state.unread_order,
         unread_counter: state.unread_counter,
         hidden_pane_ids: &state.hidden_pane_ids,
         hidden_groups: &state.hidden_groups,
+        exited_sessions: &state.exited_sessions,
+        prompt_queues: &state.prompt_queues,
+        saved_views: &state.saved_views,
+        pinned_sessions: &state.pinned_sessions,
+        pinned_groups: &state.pinned_groups,
+        session_tags: &state.session_tags,
+        session_notes: &state.session_notes,
+        session_names: &state.session_names,
+        group_aliases: &state.group_aliases,
+        session_worktrees: &state.session_worktrees,
+        last_launch_profiles: &state.last_launch_profiles,
+        manual_order: &state.manual_order,
         instance,
         shared_state: state.config.shared_state,
     });
 }
 
+fn show_toast(state: &mut AppState, message: String) {
+    state.toast_message = Some(message);
+    state.toast_deadline = Some(std::time::Instant::now() + std::time::Duration::from_millis(1500));
+}
+
 fn hide_toggle_refresh(
     state: &mut AppState,
     selected_pane_target: &watch::Sender<Option<PreviewTarget>>,
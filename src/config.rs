@@ -1,4 +1,10 @@
+use crate::formatter::FormatterMode;
+use crate::grouping::{GroupBy, GroupRegex, GroupRegexConfig};
+use crate::launch::LaunchProfile;
 use crate::session::Agent;
+use crate::slash_commands::SlashCommand;
+use crate::template::Template;
+use crate::views::SavedView;
 use serde::Deserialize;
 use std::path::PathBuf;
 
@@ -33,6 +39,28 @@ impl<'de> Deserialize<'de> for PreviewScrollMode {
     }
 }
 
+/// What a broadcast does with marked sessions that are still busy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BroadcastBusyMode {
+    #[default]
+    Queue,
+    Skip,
+}
+
+impl<'de> Deserialize<'de> for BroadcastBusyMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "queue" => Ok(BroadcastBusyMode::Queue),
+            "skip" => Ok(BroadcastBusyMode::Skip),
+            _ => Err(serde::de::Error::unknown_variant(&s, &["queue", "skip"])),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for LayoutDirection {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -60,11 +88,25 @@ struct ClaudeCodeConfigFile {
 #[serde(rename_all = "camelCase")]
 struct ConfigFile {
     session_name_formatter: Option<String>,
+    session_name_formatter_mode: Option<FormatterMode>,
+    session_name_formatter_timeout_ms: Option<u64>,
+    session_name_template: Option<String>,
     command: Option<String>,
     default_view: Option<String>,
     layout: Option<LayoutDirection>,
     shared_state: Option<bool>,
     group_name_separator: Option<String>,
+    stuck_threshold_secs: Option<u64>,
+    broadcast_busy: Option<BroadcastBusyMode>,
+    slash_commands: Option<Vec<SlashCommand>>,
+    bulk_kill_confirm_threshold: Option<usize>,
+    views: Option<Vec<SavedView>>,
+    group_by: Option<GroupBy>,
+    group_by_regex: Option<GroupRegexConfig>,
+    rename_tmux_session: Option<bool>,
+    git_status: Option<bool>,
+    worktree_dir: Option<String>,
+    launch_profiles: Option<Vec<LaunchProfile>>,
     claude_code: Option<ClaudeCodeConfigFile>,
 }
 
@@ -72,14 +114,59 @@ pub struct AppConfig {
     pub command: String,
     pub exit_on_switch: bool,
     pub session_name_formatter: Option<Vec<String>>,
+    pub session_name_formatter_mode: FormatterMode,
+    /// A formatter that takes longer than this to answer is given up on and
+    /// left alone for a while.
+    pub session_name_formatter_timeout_ms: u64,
+    /// Display-name template, used instead of the formatter when set.
+    /// `None` when unset or invalid.
+    pub session_name_template: Option<Template>,
     pub default_flat_view: bool,
     pub layout: LayoutDirection,
     pub shared_state: bool,
     pub group_name_separator: Option<String>,
+    /// Busy sessions with no output change for this long are flagged as stuck.
+    /// `0` disables detection.
+    pub stuck_threshold_secs: u64,
+    pub broadcast_busy: BroadcastBusyMode,
+    /// Custom slash commands offered by the palette alongside the built-ins.
+    pub slash_commands: Vec<SlashCommand>,
+    /// Killing at least this many marked sessions requires typing `kill`.
+    pub bulk_kill_confirm_threshold: usize,
+    /// Views defined in config, listed before the ones saved from the dashboard.
+    pub views: Vec<SavedView>,
+    /// Grouping used until one is picked at runtime.
+    pub group_by: GroupBy,
+    /// Pattern for `GroupBy::Regex`; `None` when unset or invalid.
+    pub group_by_regex: Option<GroupRegex>,
+    /// Renaming a tmux-session group also runs `tmux rename-session`.
+    pub rename_tmux_session: bool,
+    /// Show each session's git branch, dirty marker and ahead/behind counts.
+    pub git_status: bool,
+    /// Directory new worktrees are created under, one subdirectory per
+    /// repository. `None` puts them next to the repository.
+    pub worktree_dir: Option<PathBuf>,
+    /// Profiles offered by `c` in addition to one running `command`.
+    pub launch_profiles: Vec<LaunchProfile>,
     pub claude_code_preview_scroll_mode: PreviewScrollMode,
 }
 
 impl AppConfig {
+    /// Command that launches `agent`: the configured command when it runs that
+    /// agent, otherwise the agent's own binary.
+    pub fn command_for(&self, agent: Agent) -> String {
+        if self.command_agent() == agent {
+            self.command.clone()
+        } else {
+            agent.binary().to_string()
+        }
+    }
+
+    /// Agent launched by the configured `command`.
+    pub fn command_agent(&self) -> Agent {
+        agent_for_command(&self.command)
+    }
+
     pub fn effective_scroll_mode(&self, agent: Agent) -> PreviewScrollMode {
         match agent {
             Agent::Opencode => PreviewScrollMode::Virtualized,
@@ -88,6 +175,10 @@ impl AppConfig {
     }
 }
 
+const DEFAULT_FORMATTER_TIMEOUT_MS: u64 = 1000;
+const DEFAULT_STUCK_THRESHOLD_SECS: u64 = 10 * 60;
+const DEFAULT_BULK_KILL_CONFIRM_THRESHOLD: usize = 5;
+
 fn config_path() -> PathBuf {
     dirs::home_dir()
         .expect("home directory not found")
@@ -95,7 +186,18 @@ fn config_path() -> PathBuf {
 }
 
 pub fn load_config(exit_on_switch: bool) -> AppConfig {
-    let config_file = load_config_file();
+    config_from_file(load_config_file(), exit_on_switch)
+}
+
+/// Built-in defaults, without reading the user's config file.
+#[cfg(test)]
+impl Default for AppConfig {
+    fn default() -> Self {
+        config_from_file(None, false)
+    }
+}
+
+fn config_from_file(config_file: Option<ConfigFile>, exit_on_switch: bool) -> AppConfig {
     let command = config_file
         .as_ref()
         .and_then(|c| c.command.clone())
@@ -105,6 +207,21 @@ pub fn load_config(exit_on_switch: bool) -> AppConfig {
         .and_then(|c| c.session_name_formatter.as_ref())
         .map(|s| parse_formatter_command(s));
 
+    let session_name_formatter_mode = config_file
+        .as_ref()
+        .and_then(|c| c.session_name_formatter_mode)
+        .unwrap_or_default();
+
+    let session_name_formatter_timeout_ms = config_file
+        .as_ref()
+        .and_then(|c| c.session_name_formatter_timeout_ms)
+        .unwrap_or(DEFAULT_FORMATTER_TIMEOUT_MS);
+
+    let session_name_template = config_file
+        .as_ref()
+        .and_then(|c| c.session_name_template.as_deref())
+        .and_then(|t| Template::parse(t).ok());
+
     let default_flat_view = config_file
         .as_ref()
         .and_then(|c| c.default_view.as_deref())
@@ -124,6 +241,61 @@ pub fn load_config(exit_on_switch: bool) -> AppConfig {
         .as_ref()
         .and_then(|c| c.group_name_separator.clone());
 
+    let stuck_threshold_secs = config_file
+        .as_ref()
+        .and_then(|c| c.stuck_threshold_secs)
+        .unwrap_or(DEFAULT_STUCK_THRESHOLD_SECS);
+
+    let broadcast_busy = config_file
+        .as_ref()
+        .and_then(|c| c.broadcast_busy)
+        .unwrap_or_default();
+
+    let slash_commands = config_file
+        .as_ref()
+        .and_then(|c| c.slash_commands.clone())
+        .unwrap_or_default();
+
+    let bulk_kill_confirm_threshold = config_file
+        .as_ref()
+        .and_then(|c| c.bulk_kill_confirm_threshold)
+        .unwrap_or(DEFAULT_BULK_KILL_CONFIRM_THRESHOLD);
+
+    let views = config_file
+        .as_ref()
+        .and_then(|c| c.views.clone())
+        .unwrap_or_default();
+
+    let group_by = config_file
+        .as_ref()
+        .and_then(|c| c.group_by)
+        .unwrap_or_default();
+
+    let group_by_regex = config_file
+        .as_ref()
+        .and_then(|c| c.group_by_regex.as_ref())
+        .and_then(GroupRegex::new);
+
+    let rename_tmux_session = config_file
+        .as_ref()
+        .and_then(|c| c.rename_tmux_session)
+        .unwrap_or(false);
+
+    let git_status = config_file
+        .as_ref()
+        .and_then(|c| c.git_status)
+        .unwrap_or(true);
+
+    let worktree_dir = config_file
+        .as_ref()
+        .and_then(|c| c.worktree_dir.as_deref())
+        .map(expand_tilde);
+
+    let launch_profiles = config_file
+        .as_ref()
+        .and_then(|c| c.launch_profiles.clone())
+        .unwrap_or_default();
+
     let claude_code_preview_scroll_mode = config_file
         .as_ref()
         .and_then(|c| c.claude_code.as_ref())
@@ -134,10 +306,24 @@ pub fn load_config(exit_on_switch: bool) -> AppConfig {
         command,
         exit_on_switch,
         session_name_formatter,
+        session_name_formatter_mode,
+        session_name_formatter_timeout_ms,
+        session_name_template,
         default_flat_view,
         layout,
         shared_state,
         group_name_separator,
+        stuck_threshold_secs,
+        broadcast_busy,
+        slash_commands,
+        bulk_kill_confirm_threshold,
+        views,
+        group_by,
+        group_by_regex,
+        rename_tmux_session,
+        git_status,
+        worktree_dir,
+        launch_profiles,
         claude_code_preview_scroll_mode,
     }
 }
@@ -159,7 +345,16 @@ fn parse_formatter_command(s: &str) -> Vec<String> {
     result
 }
 
-fn expand_tilde(path: &str) -> PathBuf {
+/// Agent a launch command runs, going by how the command ends.
+pub fn agent_for_command(command: &str) -> Agent {
+    if command.ends_with("opencode") {
+        Agent::Opencode
+    } else {
+        Agent::Claude
+    }
+}
+
+pub fn expand_tilde(path: &str) -> PathBuf {
     if let Some(rest) = path.strip_prefix("~/") {
         dirs::home_dir()
             .expect("home directory not found")
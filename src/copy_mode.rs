@@ -320,6 +320,21 @@ pub fn handle_copy_mode_key(state: &mut AppState, key: KeyEvent) -> Option<Actio
                 return None;
             }
         }
+        KeyCode::Char('[') | KeyCode::Char(']') if state.diff_view.is_some() => {
+            let forward = key.code == KeyCode::Char(']');
+            let row = state.copy_mode.as_ref().unwrap().cursor.row;
+            let target = state
+                .diff_view
+                .as_ref()
+                .and_then(|v| v.adjacent_file(row, forward));
+            let copy = state.copy_mode.as_mut().unwrap();
+            copy.pending_g = false;
+            copy.pending_z = false;
+            if let Some(row) = target {
+                copy.cursor.row = row;
+                copy.cursor.col = 0;
+            }
+        }
         KeyCode::Char('G') => {
             let copy = state.copy_mode.as_mut().unwrap();
             copy.pending_g = false;
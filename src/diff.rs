@@ -0,0 +1,260 @@
+use crate::app::Message;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const GIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const RESET: &str = "\x1b[0m";
+const FILE_HEADER: &str = "\x1b[1;38;2;229;192;123m";
+const META: &str = "\x1b[38;2;102;102;102m";
+const HUNK: &str = "\x1b[38;2;86;182;194m";
+const ADDED: &str = "\x1b[38;2;152;195;121m";
+const REMOVED: &str = "\x1b[38;2;224;108;117m";
+
+/// The working-tree diff shown in the preview pane in place of the pane's
+/// content.
+pub struct DiffView {
+    /// Pane whose repository is shown; the view follows the selection.
+    pub pane_id: String,
+    pub repo_root: PathBuf,
+    /// Content line where each file (and the untracked list) starts.
+    pub file_starts: Vec<usize>,
+}
+
+impl DiffView {
+    /// Line of the first file starting after `row`, or before it when
+    /// `forward` is false. Files starting past the last line a `u16` scroll
+    /// offset can reach are skipped.
+    pub fn adjacent_file(&self, row: u16, forward: bool) -> Option<u16> {
+        let row = usize::from(row);
+        let start = if forward {
+            self.file_starts.iter().copied().find(|&start| start > row)
+        } else {
+            self.file_starts
+                .iter()
+                .copied()
+                .rev()
+                .find(|&start| start < row)
+        };
+        start.and_then(|start| u16::try_from(start).ok())
+    }
+
+    /// 1-based index of the file containing `row`.
+    pub fn file_at(&self, row: u16) -> usize {
+        self.file_starts
+            .iter()
+            .filter(|&&start| start <= usize::from(row))
+            .count()
+    }
+}
+
+/// Colors `git diff` output and appends the untracked files, returning the
+/// text (with ANSI colors) and the line each file starts on.
+pub fn render_diff(diff: &str, untracked: &[String]) -> (String, Vec<usize>) {
+    let mut out = String::new();
+    let mut file_starts = Vec::new();
+    let mut row: usize = 0;
+    let mut in_header = false;
+    let push = |out: &mut String, color: &str, line: &str| {
+        out.push_str(color);
+        out.push_str(line);
+        out.push_str(RESET);
+        out.push('\n');
+    };
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            if row > 0 {
+                push(&mut out, "", "");
+                row += 1;
+            }
+            file_starts.push(row);
+            in_header = true;
+            push(&mut out, FILE_HEADER, diff_path(line));
+        } else if in_header && !line.starts_with("@@") {
+            // index, mode and ---/+++ lines up to the first hunk
+            if !line.starts_with("index ") && !line.starts_with("--- ") && !line.starts_with("+++ ")
+            {
+                push(&mut out, META, line);
+            } else {
+                continue;
+            }
+        } else if line.starts_with("@@") {
+            in_header = false;
+            push(&mut out, HUNK, line);
+        } else if line.starts_with('+') {
+            push(&mut out, ADDED, line);
+        } else if line.starts_with('-') {
+            push(&mut out, REMOVED, line);
+        } else if line.starts_with('\\') {
+            push(&mut out, META, line);
+        } else {
+            out.push_str(line);
+            out.push('\n');
+        }
+        row += 1;
+    }
+
+    if !untracked.is_empty() {
+        if row > 0 {
+            push(&mut out, "", "");
+            row += 1;
+        }
+        file_starts.push(row);
+        push(&mut out, FILE_HEADER, "Untracked files");
+        for path in untracked {
+            push(&mut out, ADDED, &format!("  {}", path));
+        }
+    }
+    (out.trim_end().to_string(), file_starts)
+}
+
+/// `b/` path of a `diff --git a/x b/x` line.
+fn diff_path(line: &str) -> &str {
+    let paths = line.trim_start_matches("diff --git ");
+    match paths.rfind(" b/") {
+        Some(i) => &paths[i + 3..],
+        None => paths,
+    }
+}
+
+async fn git(root: &Path, args: &[&str]) -> Option<String> {
+    crate::git::run(root, args, GIT_TIMEOUT).await.ok()
+}
+
+/// Staged and unstaged changes of the repository at `root` against HEAD,
+/// plus its untracked files. `None` when git fails.
+pub async fn load(root: &Path) -> Option<(String, Vec<String>)> {
+    let diff_args = ["diff", "--no-color", "--no-ext-diff"];
+    let diff = match git(root, &[&diff_args[..], &["HEAD"]].concat()).await {
+        Some(diff) => diff,
+        // No commits yet: everything is staged or unstaged
+        None => {
+            let staged = git(root, &[&diff_args[..], &["--cached"]].concat()).await?;
+            let unstaged = git(root, &diff_args).await?;
+            staged + &unstaged
+        }
+    };
+    let untracked = git(root, &["ls-files", "--others", "--exclude-standard"])
+        .await?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    Some((diff, untracked))
+}
+
+/// A rendered diff, ready to show in the preview.
+pub struct LoadedDiff {
+    pub repo_root: PathBuf,
+    pub text: String,
+    pub file_starts: Vec<usize>,
+}
+
+async fn load_rendered(cwd: &str) -> Result<LoadedDiff, String> {
+    let repo_root =
+        crate::git::repo_root(Path::new(cwd)).ok_or("Not in a git repository".to_string())?;
+    let (diff, untracked) = load(&repo_root)
+        .await
+        .ok_or("git diff failed".to_string())?;
+    let (text, file_starts) = render_diff(&diff, &untracked);
+    Ok(LoadedDiff {
+        repo_root,
+        text,
+        file_starts,
+    })
+}
+
+/// Loads the diff of the repository containing `cwd` off the UI loop and
+/// sends it as `Message::DiffLoaded`. Aborting the task kills its git
+/// processes.
+pub fn spawn_load(
+    tx: mpsc::UnboundedSender<Message>,
+    pane_id: String,
+    cwd: String,
+) -> tokio::task::AbortHandle {
+    tokio::spawn(async move {
+        let diff = load_rendered(&cwd).await;
+        let _ = tx.send(Message::DiffLoaded { pane_id, diff });
+    })
+    .abort_handle()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@ fn main() {
+ fn main() {
+-    println!(\"hi\");
++    println!(\"hello\");
+ }
+diff --git a/new.txt b/new.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++new
+";
+
+    fn plain(text: &str) -> Vec<String> {
+        let text = ansi_to_tui::IntoText::into_text(&text).unwrap();
+        text.lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn collapses_headers_and_records_file_starts() {
+        let (text, starts) = render_diff(DIFF, &["notes.md".to_string()]);
+        let lines = plain(&text);
+        assert_eq!(lines[0], "src/main.rs");
+        assert_eq!(lines[1], "@@ -1,3 +1,3 @@ fn main() {");
+        assert_eq!(lines[3], "-    println!(\"hi\");");
+        assert_eq!(lines[starts[1]], "new.txt");
+        assert_eq!(lines[starts[1] + 1], "new file mode 100644");
+        assert_eq!(lines[starts[2]], "Untracked files");
+        assert_eq!(lines.last().unwrap(), "  notes.md");
+        assert_eq!(starts, vec![0, 7, 12]);
+    }
+
+    #[test]
+    fn navigates_between_files() {
+        let view = DiffView {
+            pane_id: "%1".to_string(),
+            repo_root: PathBuf::from("/repo"),
+            file_starts: vec![0, 7, 11],
+        };
+        assert_eq!(view.adjacent_file(0, true), Some(7));
+        assert_eq!(view.adjacent_file(8, true), Some(11));
+        assert_eq!(view.adjacent_file(11, true), None);
+        assert_eq!(view.adjacent_file(8, false), Some(7));
+        assert_eq!(view.adjacent_file(7, false), Some(0));
+        assert_eq!(view.file_at(9), 2);
+    }
+
+    #[test]
+    fn counts_rows_past_u16() {
+        let body: String = (0..70_000).map(|i| format!("+line {}\n", i)).collect();
+        let diff = format!(
+            "diff --git a/big.lock b/big.lock\n@@ -0,0 +1 @@\n{}diff --git a/x b/x\n",
+            body
+        );
+        let (_, starts) = render_diff(&diff, &[]);
+        assert_eq!(starts, vec![0, 70_003]);
+        let view = DiffView {
+            pane_id: "%1".to_string(),
+            repo_root: PathBuf::from("/repo"),
+            file_starts: starts,
+        };
+        assert_eq!(view.adjacent_file(10, true), None);
+        assert_eq!(view.file_at(u16::MAX), 1);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::session::{Agent, SessionStatus};
+use crate::session::{Agent, AgentSession, SessionStatus};
 use serde::Deserialize;
 use std::path::PathBuf;
 
@@ -44,6 +44,38 @@ pub fn read(pane_id: &str) -> Option<Enrichment> {
     serde_json::from_str::<Enrichment>(&content).ok()
 }
 
+/// Merges per-pane enrichment files written by agent plugins into `sessions`.
+/// Missing or malformed files are silently ignored — base detection remains
+/// the fallback.
+pub fn merge_into(sessions: &mut [AgentSession]) {
+    for session in sessions.iter_mut() {
+        let Some(enrichment) = read(&session.pane_id) else {
+            continue;
+        };
+        if enrichment.agent != session.agent {
+            continue;
+        }
+        if let Some(status) = enrichment.status_as_session_status() {
+            session.status = status;
+        }
+        if enrichment.session_id.is_some() {
+            session.session_id = enrichment.session_id;
+        }
+        if let Some(title) = enrichment.title {
+            session.title = title;
+        }
+        if enrichment.cwd.is_some() {
+            session.cwd = enrichment.cwd;
+        }
+        if enrichment.model.is_some() {
+            session.model = enrichment.model;
+        }
+        if enrichment.agent_role.is_some() {
+            session.agent_role = enrichment.agent_role;
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub fn list_pane_ids() -> Vec<String> {
     let dir = enrichment_dir();
@@ -1,25 +1,172 @@
+use crate::session::{Agent, PromptState, SessionStatus};
+
+/// A parsed session filter.
+///
+/// Qualifier tokens narrow the list; everything else is free text matched
+/// fuzzily. Qualifiers are ANDed, `OR` (or `|`) starts an alternative group,
+/// and a leading `-` negates a qualifier.
 pub struct ParsedQuery {
     pub include_hidden: bool,
     pub text: String,
+    /// Alternatives of ANDed conditions; empty when the query has none.
+    pub groups: Vec<Vec<Condition>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub negated: bool,
+    pub qualifier: Qualifier,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Qualifier {
+    Active,
+    Idle,
+    Unread,
+    Ask,
+    Plan,
+    Stuck,
+    Agent(String),
+    Model(String),
+    Cwd(String),
+    Group(String),
+    Role(String),
+    Tag(String),
+}
+
+/// What a session looks like to the filter.
+pub struct SessionFacts<'a> {
+    pub status: &'a SessionStatus,
+    pub is_unread: bool,
+    pub is_stuck: bool,
+    pub prompt_state: &'a PromptState,
+    pub agent: Agent,
+    pub model: Option<&'a str>,
+    pub cwd: Option<&'a str>,
+    /// tmux session name and, when different, its formatted display name.
+    pub group_names: Vec<&'a str>,
+    pub role: Option<&'a str>,
+    /// Tags the user attached to the session.
+    pub tags: &'a [String],
+}
+
+impl ParsedQuery {
+    /// Whether the session satisfies the qualifiers (free text is matched
+    /// separately).
+    pub fn matches(&self, facts: &SessionFacts) -> bool {
+        self.groups.is_empty()
+            || self
+                .groups
+                .iter()
+                .any(|group| group.iter().all(|c| c.matches(facts)))
+    }
+}
+
+impl Condition {
+    fn matches(&self, facts: &SessionFacts) -> bool {
+        let contains = |value: Option<&str>, needle: &str| {
+            value.is_some_and(|v| v.to_lowercase().contains(needle))
+        };
+        let result = match &self.qualifier {
+            Qualifier::Active => *facts.status == SessionStatus::Active,
+            Qualifier::Idle => *facts.status == SessionStatus::Idle,
+            Qualifier::Unread => facts.is_unread,
+            Qualifier::Ask => *facts.prompt_state == PromptState::Ask,
+            Qualifier::Plan => *facts.prompt_state == PromptState::Plan,
+            Qualifier::Stuck => facts.is_stuck,
+            Qualifier::Agent(name) => facts.agent.binary().starts_with(name.as_str()),
+            Qualifier::Model(needle) => contains(facts.model, needle),
+            Qualifier::Cwd(needle) => contains(facts.cwd, needle),
+            Qualifier::Group(needle) => facts
+                .group_names
+                .iter()
+                .any(|name| contains(Some(name), needle)),
+            Qualifier::Role(needle) => contains(facts.role, needle),
+            Qualifier::Tag(tag) => facts.tags.iter().any(|t| t.to_lowercase() == *tag),
+        };
+        result != self.negated
+    }
+}
+
+fn is_or_token(token: &str) -> bool {
+    token == "OR" || token == "|"
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) => format!("{}{}", home.to_string_lossy(), rest),
+        _ => path.to_string(),
+    }
+}
+
+/// Parses a qualifier token such as `is:idle`, `-agent:claude` or
+/// `cwd:~/work`. `is:hidden` is handled by the caller.
+fn parse_condition(token: &str) -> Option<Condition> {
+    let (negated, body) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, token),
+    };
+    let (key, value) = body.split_once(':')?;
+    if value.is_empty() {
+        return None;
+    }
+    let key = key.to_lowercase();
+    let lowered = value.to_lowercase();
+    let qualifier = match key.as_str() {
+        "is" => match lowered.as_str() {
+            "active" | "busy" => Qualifier::Active,
+            "idle" => Qualifier::Idle,
+            "unread" => Qualifier::Unread,
+            "ask" => Qualifier::Ask,
+            "plan" => Qualifier::Plan,
+            "stuck" => Qualifier::Stuck,
+            _ => return None,
+        },
+        "agent" => Qualifier::Agent(lowered),
+        "model" => Qualifier::Model(lowered),
+        "cwd" => Qualifier::Cwd(expand_home(value).to_lowercase()),
+        "group" => Qualifier::Group(lowered),
+        "role" => Qualifier::Role(lowered),
+        "tag" => Qualifier::Tag(lowered),
+        _ => return None,
+    };
+    Some(Condition { negated, qualifier })
+}
+
+fn is_hidden_token(token: &str) -> bool {
+    matches!(token.to_lowercase().as_str(), "is:h" | "is:hidden")
 }
 
 pub fn parse_filter_query(raw: &str) -> ParsedQuery {
     let mut include_hidden = false;
     let mut text_tokens: Vec<&str> = Vec::new();
+    let mut groups: Vec<Vec<Condition>> = vec![Vec::new()];
 
     for token in raw.split_whitespace() {
-        match token.to_lowercase().as_str() {
-            "is:h" | "is:hidden" => include_hidden = true,
-            _ => text_tokens.push(token),
+        if is_hidden_token(token) {
+            include_hidden = true;
+        } else if is_or_token(token) {
+            groups.push(Vec::new());
+        } else if let Some(condition) = parse_condition(token) {
+            groups.last_mut().unwrap().push(condition);
+        } else {
+            text_tokens.push(token);
         }
     }
+    groups.retain(|g| !g.is_empty());
 
     ParsedQuery {
         include_hidden,
         text: text_tokens.join(" "),
+        groups,
     }
 }
 
+/// Whether `token` is consumed as a qualifier or operator rather than free text.
+pub fn is_qualifier_token(token: &str) -> bool {
+    is_hidden_token(token) || is_or_token(token) || parse_condition(token).is_some()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,6 +199,70 @@ mod tests {
         assert_eq!(q.text, "");
     }
 
+    fn facts<'a>(status: &'a SessionStatus, group_names: &[&'a str]) -> SessionFacts<'a> {
+        SessionFacts {
+            status,
+            is_unread: false,
+            is_stuck: false,
+            prompt_state: &PromptState::None,
+            agent: Agent::Claude,
+            model: Some("claude-opus-4"),
+            cwd: Some("/home/me/work/api"),
+            group_names: group_names.to_vec(),
+            role: None,
+            tags: &[],
+        }
+    }
+
+    #[test]
+    fn is_stuck() {
+        let q = parse_filter_query("is:stuck api");
+        assert_eq!(
+            q.groups,
+            vec![vec![Condition {
+                negated: false,
+                qualifier: Qualifier::Stuck
+            }]]
+        );
+        assert!(!q.include_hidden);
+        assert_eq!(q.text, "api");
+    }
+
+    #[test]
+    fn qualifiers_are_anded() {
+        let q = parse_filter_query("is:idle agent:claude model:opus");
+        assert_eq!(q.groups[0].len(), 3);
+        assert!(q.matches(&facts(&SessionStatus::Idle, &["main"])));
+        assert!(!q.matches(&facts(&SessionStatus::Active, &["main"])));
+    }
+
+    #[test]
+    fn negation() {
+        let q = parse_filter_query("-is:idle");
+        assert!(!q.matches(&facts(&SessionStatus::Idle, &["main"])));
+        assert!(q.matches(&facts(&SessionStatus::Active, &["main"])));
+    }
+
+    #[test]
+    fn or_groups() {
+        let q = parse_filter_query("group:web OR is:active | cwd:/nowhere");
+        assert_eq!(q.groups.len(), 3);
+        assert!(q.matches(&facts(&SessionStatus::Active, &["api"])));
+        assert!(q.matches(&facts(&SessionStatus::Idle, &["api", "Web App"])));
+        assert!(!q.matches(&facts(&SessionStatus::Idle, &["api"])));
+    }
+
+    #[test]
+    fn cwd_and_highlighting() {
+        let q = parse_filter_query("cwd:work/api fix");
+        assert!(q.matches(&facts(&SessionStatus::Idle, &["main"])));
+        assert_eq!(q.text, "fix");
+        assert!(is_qualifier_token("-role:reviewer"));
+        assert!(is_qualifier_token("OR"));
+        assert!(!is_qualifier_token("agent:"));
+        assert!(!is_qualifier_token("fix"));
+    }
+
     #[test]
     fn unknown_is_token_passes_through() {
         let q = parse_filter_query("is:other foo");
@@ -65,4 +276,15 @@ mod tests {
         assert!(!q.include_hidden);
         assert_eq!(q.text, "");
     }
+
+    #[test]
+    fn tags_match_whole_tag() {
+        let tags = vec!["Review".to_string(), "JIRA-123".to_string()];
+        let mut session = facts(&SessionStatus::Idle, &["main"]);
+        session.tags = &tags;
+        assert!(parse_filter_query("tag:review").matches(&session));
+        assert!(parse_filter_query("tag:jira-123").matches(&session));
+        assert!(!parse_filter_query("tag:rev").matches(&session));
+        assert!(parse_filter_query("-tag:blocked").matches(&session));
+    }
 }
@@ -0,0 +1,287 @@
+use crate::session::AgentSession;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+/// Formatted names are asked for again after this long, so formatters that
+/// look at external state (branches, ticket titles) catch up eventually.
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+/// How long a formatter that timed out or failed is left alone.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// How `sessionNameFormatter` is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FormatterMode {
+    /// One process per name, which gets the tmux session name as its last
+    /// argument and prints the display name.
+    #[default]
+    Spawn,
+    /// A single long-lived process reading one JSON request per line on
+    /// stdin and answering each with one JSON line on stdout.
+    Persistent,
+}
+
+/// What the formatter knows about a tmux session. Only `name` reaches a
+/// spawned formatter; a persistent one gets all of it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatContext {
+    pub name: String,
+    pub cwd: Option<String>,
+    pub branch: Option<String>,
+    pub agent: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    id: u64,
+    #[serde(flatten)]
+    context: &'a FormatContext,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    id: u64,
+    name: Option<String>,
+}
+
+struct FormatterProcess {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+/// Runs the configured session-name formatter and caches its answers by
+/// session context.
+pub struct Formatter {
+    command: Vec<String>,
+    mode: FormatterMode,
+    timeout: Duration,
+    cache: HashMap<FormatContext, (String, Instant)>,
+    process: Option<FormatterProcess>,
+    next_id: u64,
+    retry_at: Option<Instant>,
+}
+
+impl Formatter {
+    pub fn new(command: Vec<String>, mode: FormatterMode, timeout: Duration) -> Self {
+        Formatter {
+            command,
+            mode,
+            timeout,
+            cache: HashMap::new(),
+            process: None,
+            next_id: 1,
+            retry_at: None,
+        }
+    }
+
+    /// Display names keyed by tmux session name. A cached name is reused
+    /// while its session's context is unchanged and younger than the TTL;
+    /// sessions the formatter can't answer for keep their raw name.
+    pub async fn format_all(&mut self, contexts: &[FormatContext]) -> HashMap<String, String> {
+        let now = Instant::now();
+        self.cache.retain(|context, (_, at)| {
+            contexts.contains(context) && now.duration_since(*at) < CACHE_TTL
+        });
+        let mut names = HashMap::new();
+        for context in contexts {
+            let name = match self.cache.get(context) {
+                Some((name, _)) => name.clone(),
+                None => match self.format(context).await {
+                    Some(name) => {
+                        self.cache
+                            .insert(context.clone(), (name.clone(), Instant::now()));
+                        name
+                    }
+                    None => context.name.clone(),
+                },
+            };
+            names.insert(context.name.clone(), name);
+        }
+        names
+    }
+
+    async fn format(&mut self, context: &FormatContext) -> Option<String> {
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return None;
+        }
+        let result = match self.mode {
+            FormatterMode::Spawn => self.spawn_once(&context.name).await,
+            FormatterMode::Persistent => self.request(context).await,
+        };
+        match result {
+            Ok(name) => name,
+            Err(()) => {
+                self.process = None;
+                self.retry_at = Some(Instant::now() + RETRY_DELAY);
+                None
+            }
+        }
+    }
+
+    /// `Err` means the formatter is broken or hung, `Ok(None)` that it had
+    /// no name for this session.
+    async fn spawn_once(&self, name: &str) -> Result<Option<String>, ()> {
+        let (cmd, pre_args) = self.command.split_first().ok_or(())?;
+        let output = Command::new(cmd)
+            .args(pre_args)
+            .arg(name)
+            .kill_on_drop(true)
+            .output();
+        match tokio::time::timeout(self.timeout, output).await {
+            Ok(Ok(output)) if output.status.success() => {
+                let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                Ok(Some(name).filter(|n| !n.is_empty()))
+            }
+            Ok(Ok(_)) => Ok(None),
+            _ => Err(()),
+        }
+    }
+
+    async fn request(&mut self, context: &FormatContext) -> Result<Option<String>, ()> {
+        if self.process.is_none() {
+            self.process = Some(spawn_process(&self.command).ok_or(())?);
+        }
+        let process = self.process.as_mut().ok_or(())?;
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut line = serde_json::to_string(&Request { id, context }).map_err(|_| ())?;
+        line.push('\n');
+
+        let exchange = async {
+            process.stdin.write_all(line.as_bytes()).await.ok()?;
+            process.stdin.flush().await.ok()?;
+            loop {
+                let reply = process.stdout.next_line().await.ok()??;
+                // Skip blank lines and replies to other requests
+                if let Some(response) = parse_response(&reply) {
+                    if response.id == id {
+                        return Some(response.name.filter(|n| !n.is_empty()));
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(self.timeout, exchange)
+            .await
+            .ok()
+            .flatten()
+            .ok_or(())
+    }
+}
+
+/// Context of each tmux session, from its lead session.
+pub fn session_contexts(sessions: &[AgentSession]) -> Vec<FormatContext> {
+    crate::session::tmux_session_leads(sessions)
+        .into_iter()
+        .map(|session| {
+            let branch = session
+                .cwd
+                .as_deref()
+                .and_then(|cwd| crate::git::repo_root(Path::new(cwd)))
+                .and_then(|root| crate::git::current_branch(&root));
+            FormatContext {
+                name: session.tmux_session_name.clone(),
+                cwd: session.cwd.clone(),
+                branch,
+                agent: Some(session.agent.binary().to_string()),
+            }
+        })
+        .collect()
+}
+
+fn spawn_process(command: &[String]) -> Option<FormatterProcess> {
+    let (cmd, args) = command.split_first()?;
+    let mut child = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+    let stdin = child.stdin.take()?;
+    let stdout = BufReader::new(child.stdout.take()?).lines();
+    Some(FormatterProcess {
+        _child: child,
+        stdin,
+        stdout,
+    })
+}
+
+fn parse_response(line: &str) -> Option<Response> {
+    serde_json::from_str(line.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(name: &str) -> FormatContext {
+        FormatContext {
+            name: name.to_string(),
+            cwd: Some("/work/api".to_string()),
+            branch: Some("main".to_string()),
+            agent: Some("claude".to_string()),
+        }
+    }
+
+    fn shell(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn request_is_one_flat_json_object() {
+        let ctx = context("api");
+        let line = serde_json::to_string(&Request {
+            id: 7,
+            context: &ctx,
+        })
+        .unwrap();
+        assert_eq!(
+            line,
+            r#"{"id":7,"name":"api","cwd":"/work/api","branch":"main","agent":"claude"}"#
+        );
+        assert!(parse_response("not json").is_none());
+        assert_eq!(parse_response(r#"{"id":3,"name":"x"}"#).unwrap().id, 3);
+    }
+
+    #[tokio::test]
+    async fn persistent_formatter_answers_by_id() {
+        // Echoes each request back, so the reply's name is the session name
+        let mut formatter = Formatter::new(
+            shell("while read -r line; do echo; echo \"$line\"; done"),
+            FormatterMode::Persistent,
+            Duration::from_secs(5),
+        );
+        let names = formatter
+            .format_all(&[context("api"), context("web")])
+            .await;
+        assert_eq!(names["api"], "api");
+        assert_eq!(names["web"], "web");
+        assert_eq!(formatter.cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn hung_formatter_times_out_and_backs_off() {
+        let mut formatter = Formatter::new(
+            shell("sleep 10"),
+            FormatterMode::Persistent,
+            Duration::from_millis(50),
+        );
+        let started = Instant::now();
+        let names = formatter.format_all(&[context("api")]).await;
+        assert_eq!(names["api"], "api");
+        assert!(formatter.retry_at.is_some());
+        assert!(formatter.process.is_none());
+        // Backing off answers immediately with the raw name
+        let names = formatter.format_all(&[context("web")]).await;
+        assert_eq!(names["web"], "web");
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
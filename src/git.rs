@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Root of the git work tree containing `path`: the nearest ancestor with a
+/// `.git` entry. A `.git` file (worktrees, submodules) counts as well.
+pub fn repo_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// The git directory of the work tree at `root`, following the `gitdir:`
+/// pointer of a worktree or submodule `.git` file.
+pub fn git_dir(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let target = content.trim().strip_prefix("gitdir:")?.trim();
+    Some(root.join(target))
+}
+
+/// Branch checked out in the work tree at `root`, or the abbreviated commit
+/// when HEAD is detached.
+pub fn current_branch(root: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(git_dir(root)?.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) => Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        ),
+        None => Some(head.chars().take(7).collect()),
+    }
+}
+
+/// Runs git in `dir`, killing it after `timeout`. Returns stdout, or the
+/// first line of stderr when git fails. `--no-optional-locks` keeps read-only
+/// commands from rewriting the index under a running agent.
+pub async fn run(dir: &Path, args: &[&str], timeout: Duration) -> Result<String, String> {
+    let output = tokio::process::Command::new("git")
+        .arg("--no-optional-locks")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(timeout, output)
+        .await
+        .map_err(|_| "git timed out".to_string())?
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = stderr
+        .lines()
+        .map(|l| l.trim_start_matches("fatal: ").trim())
+        .find(|l| !l.is_empty())
+        .unwrap_or("git failed");
+    Err(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_nearest_ancestor_with_git_entry() {
+        let base = std::env::temp_dir().join(format!("agent-dash-git-{}", std::process::id()));
+        let nested = base.join("repo/src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(base.join("repo/.git")).unwrap();
+        std::fs::create_dir_all(base.join("repo/src/wt")).unwrap();
+        std::fs::write(base.join("repo/src/wt/.git"), "gitdir: elsewhere").unwrap();
+
+        assert_eq!(repo_root(&nested), Some(base.join("repo")));
+        assert_eq!(
+            repo_root(&base.join("repo/src/wt")),
+            Some(base.join("repo/src/wt"))
+        );
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn reads_branch_through_gitdir_pointer() {
+        let base = std::env::temp_dir().join(format!("agent-dash-head-{}", std::process::id()));
+        std::fs::create_dir_all(base.join("main/.git/worktrees/wt")).unwrap();
+        std::fs::create_dir_all(base.join("wt")).unwrap();
+        std::fs::write(base.join("main/.git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(
+            base.join("main/.git/worktrees/wt/HEAD"),
+            "0123456789abcdef0123456789abcdef01234567\n",
+        )
+        .unwrap();
+        std::fs::write(
+            base.join("wt/.git"),
+            format!(
+                "gitdir: {}\n",
+                base.join("main/.git/worktrees/wt").display()
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(current_branch(&base.join("main")), Some("main".to_string()));
+        assert_eq!(
+            current_branch(&base.join("wt")),
+            Some("0123456".to_string())
+        );
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
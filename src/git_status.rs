@@ -0,0 +1,197 @@
+use crate::app::Message;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, watch};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(3);
+/// Unstaged edits don't touch `.git/index`, so statuses are refreshed at
+/// least this often regardless.
+const MAX_AGE: Duration = Duration::from_secs(30);
+const GIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Branch and working-tree state of a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GitStatus {
+    /// Branch name, or the abbreviated commit when HEAD is detached.
+    pub branch: String,
+    /// Staged, unstaged, conflicted or untracked changes exist.
+    pub dirty: bool,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+impl GitStatus {
+    /// Compact form for the session list, e.g. `main*↑2↓1`.
+    pub fn summary(&self) -> String {
+        let mut text = self.branch.clone();
+        if self.dirty {
+            text.push('*');
+        }
+        if self.ahead > 0 {
+            text.push_str(&format!("↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            text.push_str(&format!("↓{}", self.behind));
+        }
+        text
+    }
+}
+
+/// Parses `git status --porcelain=v2 --branch` output.
+pub fn parse_porcelain_v2(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut oid = "";
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" => oid = value,
+                "branch.head" if value != "(detached)" => status.branch = value.to_string(),
+                "branch.ab" => {
+                    for part in value.split_whitespace() {
+                        if let Some(n) = part.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        } else if !line.is_empty() && !line.starts_with('!') {
+            status.dirty = true;
+        }
+    }
+    if status.branch.is_empty() {
+        status.branch = oid.chars().take(7).collect();
+    }
+    status
+}
+
+async fn read_status(root: &Path) -> Option<GitStatus> {
+    let args = ["status", "--porcelain=v2", "--branch"];
+    let output = crate::git::run(root, &args, GIT_TIMEOUT).await.ok()?;
+    Some(parse_porcelain_v2(&output))
+}
+
+/// Modification times of the files a commit, checkout or `git add` touches.
+fn index_stamp(root: &Path) -> Option<(Option<SystemTime>, Option<SystemTime>)> {
+    let git_dir = crate::git::git_dir(root)?;
+    let mtime = |name: &str| {
+        std::fs::metadata(git_dir.join(name))
+            .and_then(|m| m.modified())
+            .ok()
+    };
+    Some((mtime("index"), mtime("HEAD")))
+}
+
+struct CacheEntry {
+    status: Option<GitStatus>,
+    stamp: Option<(Option<SystemTime>, Option<SystemTime>)>,
+    checked_at: Instant,
+}
+
+/// Watches the repositories of the cwds in `cwds_rx` and sends their status,
+/// keyed by cwd, whenever it changes. A repository is only queried again
+/// when its index or HEAD changes, or after `MAX_AGE`.
+pub fn spawn_status_task(
+    tx: mpsc::UnboundedSender<Message>,
+    mut cwds_rx: watch::Receiver<Vec<String>>,
+) {
+    tokio::spawn(async move {
+        let mut roots: HashMap<String, Option<PathBuf>> = HashMap::new();
+        let mut cache: HashMap<PathBuf, CacheEntry> = HashMap::new();
+        let mut sent: HashMap<String, GitStatus> = HashMap::new();
+        loop {
+            let cwds = cwds_rx.borrow_and_update().clone();
+            roots.retain(|cwd, _| cwds.contains(cwd));
+            for cwd in &cwds {
+                roots
+                    .entry(cwd.clone())
+                    .or_insert_with(|| crate::git::repo_root(Path::new(cwd)));
+            }
+            let live: HashSet<PathBuf> = roots.values().flatten().cloned().collect();
+            cache.retain(|root, _| live.contains(root));
+
+            for root in live {
+                let stamp = index_stamp(&root);
+                let fresh = cache
+                    .get(&root)
+                    .is_some_and(|e| e.stamp == stamp && e.checked_at.elapsed() < MAX_AGE);
+                if !fresh {
+                    let status = read_status(&root).await;
+                    let entry = CacheEntry {
+                        status,
+                        stamp,
+                        checked_at: Instant::now(),
+                    };
+                    cache.insert(root, entry);
+                }
+            }
+
+            let statuses: HashMap<String, GitStatus> = roots
+                .iter()
+                .filter_map(|(cwd, root)| {
+                    let status = cache.get(root.as_ref()?)?.status.clone()?;
+                    Some((cwd.clone(), status))
+                })
+                .collect();
+            if statuses != sent {
+                sent = statuses.clone();
+                if tx.send(Message::GitStatusChanged(statuses)).is_err() {
+                    return;
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(CHECK_INTERVAL) => {}
+                result = cwds_rx.changed() => {
+                    if result.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_branch_counts_and_changes() {
+        let output = "\
+# branch.oid 0123456789abcdef0123456789abcdef01234567
+# branch.head feature/login
+# branch.upstream origin/feature/login
+# branch.ab +2 -1
+1 .M N... 100644 100644 100644 aaaa bbbb src/main.rs
+? notes.txt
+";
+        let status = parse_porcelain_v2(output);
+        assert_eq!(
+            status,
+            GitStatus {
+                branch: "feature/login".to_string(),
+                dirty: true,
+                ahead: 2,
+                behind: 1,
+            }
+        );
+        assert_eq!(status.summary(), "feature/login*↑2↓1");
+    }
+
+    #[test]
+    fn clean_detached_head_shows_commit() {
+        let output = "\
+# branch.oid 0123456789abcdef0123456789abcdef01234567
+# branch.head (detached)
+! target/
+";
+        let status = parse_porcelain_v2(output);
+        assert_eq!(status.summary(), "0123456");
+        assert!(!status.dirty);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::session::AgentSession;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// What the tree view groups sessions by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupBy {
+    #[default]
+    TmuxSession,
+    /// Root of the git repository containing the session's cwd.
+    Repo,
+    Cwd,
+    Agent,
+    Model,
+    Role,
+    /// First capture of the configured `groupByRegex`.
+    Regex,
+}
+
+impl GroupBy {
+    /// Next grouping in the cycle; `Regex` is skipped unless one is configured.
+    pub fn next(self, has_regex: bool) -> Self {
+        let next = match self {
+            GroupBy::TmuxSession => GroupBy::Repo,
+            GroupBy::Repo => GroupBy::Cwd,
+            GroupBy::Cwd => GroupBy::Agent,
+            GroupBy::Agent => GroupBy::Model,
+            GroupBy::Model => GroupBy::Role,
+            GroupBy::Role => GroupBy::Regex,
+            GroupBy::Regex => GroupBy::TmuxSession,
+        };
+        if next == GroupBy::Regex && !has_regex {
+            GroupBy::TmuxSession
+        } else {
+            next
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupBy::TmuxSession => "session",
+            GroupBy::Repo => "repo",
+            GroupBy::Cwd => "cwd",
+            GroupBy::Agent => "agent",
+            GroupBy::Model => "model",
+            GroupBy::Role => "role",
+            GroupBy::Regex => "regex",
+        }
+    }
+}
+
+/// Session field a grouping regex is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupField {
+    Title,
+    Cwd,
+    Session,
+    Agent,
+    Model,
+    Role,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupRegexConfig {
+    pub field: GroupField,
+    pub pattern: String,
+}
+
+#[derive(Debug)]
+pub struct GroupRegex {
+    field: GroupField,
+    pattern: Regex,
+}
+
+impl GroupRegex {
+    /// Compiles the configured pattern; `None` if it is not a valid regex.
+    pub fn new(config: &GroupRegexConfig) -> Option<Self> {
+        let pattern = Regex::new(&config.pattern).ok()?;
+        Some(GroupRegex {
+            field: config.field,
+            pattern,
+        })
+    }
+
+    /// First capture group, or the whole match when the pattern has none.
+    fn capture(&self, session: &AgentSession) -> Option<String> {
+        let value = match self.field {
+            GroupField::Title => Some(session.title.as_str()),
+            GroupField::Cwd => session.cwd.as_deref(),
+            GroupField::Session => Some(session.tmux_session_name.as_str()),
+            GroupField::Agent => Some(session.agent.binary()),
+            GroupField::Model => session.model.as_deref(),
+            GroupField::Role => session.agent_role.as_deref(),
+        }?;
+        let captures = self.pattern.captures(value)?;
+        captures
+            .get(1)
+            .or_else(|| captures.get(0))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+/// Group key and header label of `session` under `group_by`. Keys other than
+/// tmux session names carry the grouping as a prefix (`repo:/src/api`), so
+/// collapse, hide and pin state is kept separately for each grouping.
+/// `repo_root` resolves a cwd to its repository root.
+pub fn group_key(
+    session: &AgentSession,
+    group_by: GroupBy,
+    regex: Option<&GroupRegex>,
+    mut repo_root: impl FnMut(&str) -> Option<String>,
+) -> (String, String) {
+    let value = match group_by {
+        GroupBy::TmuxSession => {
+            return (
+                session.tmux_session_name.clone(),
+                session.tmux_session_name.clone(),
+            )
+        }
+        GroupBy::Repo => session.cwd.as_deref().and_then(&mut repo_root),
+        GroupBy::Cwd => session.cwd.clone(),
+        GroupBy::Agent => Some(session.agent.binary().to_string()),
+        GroupBy::Model => session.model.clone(),
+        GroupBy::Role => session.agent_role.clone(),
+        GroupBy::Regex => regex.and_then(|r| r.capture(session)),
+    };
+    let key = format!("{}:{}", group_by.label(), value.as_deref().unwrap_or(""));
+    let label = match (group_by, value) {
+        (GroupBy::Repo, Some(root)) => root.rsplit('/').next().unwrap_or(&root).to_string(),
+        (GroupBy::Cwd, Some(cwd)) => shorten_home(&cwd),
+        (_, Some(value)) => value,
+        (GroupBy::Repo, None) => "no repo".to_string(),
+        (GroupBy::Cwd, None) => "unknown cwd".to_string(),
+        (GroupBy::Model, None) => "unknown model".to_string(),
+        (GroupBy::Role, None) => "no role".to_string(),
+        (_, None) => "other".to_string(),
+    };
+    (key, label)
+}
+
+/// `path` with the home directory replaced by `~`.
+pub fn shorten_home(path: &str) -> String {
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().into_owned());
+    match home.as_deref().and_then(|h| path.strip_prefix(h)) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("~{}", rest),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(cwd: &str) -> AgentSession {
+        AgentSession {
+            cwd: Some(cwd.to_string()),
+            ..AgentSession::test("%1")
+        }
+    }
+
+    #[test]
+    fn keys_are_prefixed_by_grouping() {
+        let s = session("/src/api/server");
+        let root = |cwd: &str| cwd.strip_suffix("/server").map(str::to_string);
+        assert_eq!(
+            group_key(&s, GroupBy::TmuxSession, None, root),
+            ("main".to_string(), "main".to_string())
+        );
+        assert_eq!(
+            group_key(&s, GroupBy::Repo, None, root),
+            ("repo:/src/api".to_string(), "api".to_string())
+        );
+        assert_eq!(
+            group_key(&s, GroupBy::Model, None, root),
+            ("model:".to_string(), "unknown model".to_string())
+        );
+        let outside = session("/tmp");
+        assert_eq!(group_key(&outside, GroupBy::Repo, None, root).1, "no repo");
+    }
+
+    #[test]
+    fn regex_uses_first_capture() {
+        let config = GroupRegexConfig {
+            field: GroupField::Cwd,
+            pattern: r"/work/([^/]+)".to_string(),
+        };
+        let regex = GroupRegex::new(&config).unwrap();
+        let s = session("/home/me/work/billing/web");
+        assert_eq!(
+            group_key(&s, GroupBy::Regex, Some(&regex), |_| None),
+            ("regex:billing".to_string(), "billing".to_string())
+        );
+        let other = session("/etc");
+        assert_eq!(
+            group_key(&other, GroupBy::Regex, Some(&regex), |_| None).1,
+            "other"
+        );
+        assert!(GroupRegex::new(&GroupRegexConfig {
+            field: GroupField::Title,
+            pattern: "(".to_string(),
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn cycle_skips_regex_without_pattern() {
+        assert_eq!(GroupBy::Role.next(false), GroupBy::TmuxSession);
+        assert_eq!(GroupBy::Role.next(true), GroupBy::Regex);
+    }
+}
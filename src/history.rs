@@ -0,0 +1,180 @@
+use crate::launch::quote;
+use crate::session::{Agent, AgentSession, SessionStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+const MAX_ENTRIES: usize = 50;
+
+/// A session that ended, kept so it can be resumed later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub session_id: String,
+    pub agent: Agent,
+    pub title: String,
+    #[serde(rename = "sessionName")]
+    pub tmux_session_name: String,
+    pub cwd: Option<String>,
+    pub model: Option<String>,
+    pub ended_at: u64,
+}
+
+impl HistoryEntry {
+    /// Only sessions with a known `session_id` can be resumed, so others are
+    /// not recorded.
+    pub fn from_session(session: &AgentSession, ended_at: u64) -> Option<Self> {
+        Some(HistoryEntry {
+            session_id: session.session_id.clone()?,
+            agent: session.agent,
+            title: session.title.clone(),
+            tmux_session_name: session.tmux_session_name.clone(),
+            cwd: session.cwd.clone(),
+            model: session.model.clone(),
+            ended_at,
+        })
+    }
+}
+
+fn history_path() -> PathBuf {
+    dirs::home_dir()
+        .expect("home directory not found")
+        .join(".config/agent-dash/history.json")
+}
+
+pub fn load_history() -> Vec<HistoryEntry> {
+    std::fs::read_to_string(history_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_history(entries: &[HistoryEntry]) {
+    let path = history_path();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(path, serde_json::to_string(entries).unwrap_or_default());
+}
+
+/// Inserts `entry` as the most recent one, replacing any older record of the
+/// same session and dropping the oldest entries beyond the cap.
+pub fn record(entries: &mut Vec<HistoryEntry>, entry: HistoryEntry) {
+    entries.retain(|e| e.session_id != entry.session_id);
+    entries.insert(0, entry);
+    entries.truncate(MAX_ENTRIES);
+}
+
+/// Entries for sessions in `previous` that exited or whose pane closed by the
+/// time `current` was discovered. An empty `live_pane_ids` means discovery
+/// failed rather than that every pane closed, so nothing is reported.
+pub fn ended_sessions(
+    previous: &[AgentSession],
+    current: &[AgentSession],
+    live_pane_ids: &HashSet<String>,
+    ended_at: u64,
+) -> Vec<HistoryEntry> {
+    if live_pane_ids.is_empty() {
+        return Vec::new();
+    }
+    let running: HashSet<&str> = current
+        .iter()
+        .filter(|s| s.status != SessionStatus::Exited)
+        .map(|s| s.pane_id.as_str())
+        .collect();
+    previous
+        .iter()
+        .filter(|s| s.status != SessionStatus::Exited && !running.contains(s.pane_id.as_str()))
+        .filter_map(|s| HistoryEntry::from_session(s, ended_at))
+        .collect()
+}
+
+/// Shell command that resumes agent session `session_id` with the given agent
+/// command.
+pub fn resume_command(command: &str, agent: Agent, session_id: &str) -> String {
+    let flag = match agent {
+        Agent::Claude => "--resume",
+        Agent::Opencode => "--session",
+    };
+    format!("{} {} {}", command, flag, quote(session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(session_id: &str, ended_at: u64) -> HistoryEntry {
+        HistoryEntry {
+            session_id: session_id.to_string(),
+            agent: Agent::Claude,
+            title: "title".to_string(),
+            tmux_session_name: "main".to_string(),
+            cwd: Some("/tmp".to_string()),
+            model: None,
+            ended_at,
+        }
+    }
+
+    #[test]
+    fn record_puts_newest_first_and_dedupes() {
+        let mut entries = vec![entry("a", 1), entry("b", 2)];
+        record(&mut entries, entry("a", 3));
+        let ids: Vec<&str> = entries.iter().map(|e| e.session_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(entries[0].ended_at, 3);
+    }
+
+    #[test]
+    fn record_caps_length() {
+        let mut entries = Vec::new();
+        for i in 0..(MAX_ENTRIES + 5) {
+            record(&mut entries, entry(&i.to_string(), i as u64));
+        }
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].session_id, (MAX_ENTRIES + 4).to_string());
+    }
+
+    #[test]
+    fn resume_command_uses_agent_flag() {
+        assert_eq!(
+            resume_command("claude --model opus", Agent::Claude, "abc"),
+            "claude --model opus --resume abc"
+        );
+        assert_eq!(
+            resume_command("opencode", Agent::Opencode, "ses_1"),
+            "opencode --session ses_1"
+        );
+        assert_eq!(
+            resume_command("claude", Agent::Claude, "a b"),
+            "claude --resume 'a b'"
+        );
+    }
+
+    fn session(pane_id: &str, session_id: Option<&str>) -> AgentSession {
+        AgentSession {
+            session_id: session_id.map(str::to_string),
+            ..AgentSession::test(pane_id)
+        }
+    }
+
+    #[test]
+    fn sessions_without_id_are_not_recorded() {
+        assert!(HistoryEntry::from_session(&session("%1", None), 0).is_none());
+    }
+
+    #[test]
+    fn ended_sessions_reports_closed_panes() {
+        let previous = vec![session("%1", Some("a")), session("%2", Some("b"))];
+        let current = vec![session("%1", Some("a"))];
+        let live: HashSet<String> = ["%1".to_string()].into();
+        let ended = ended_sessions(&previous, &current, &live, 5);
+        let ids: Vec<&str> = ended.iter().map(|e| e.session_id.as_str()).collect();
+        assert_eq!(ids, ["b"]);
+    }
+
+    #[test]
+    fn ended_sessions_ignores_failed_discovery() {
+        let previous = vec![session("%1", Some("a")), session("%2", Some("b"))];
+        assert!(ended_sessions(&previous, &[], &HashSet::new(), 5).is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+use crate::config::{agent_for_command, AppConfig};
+use crate::session::Agent;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+/// Name of the profile built from `command`, offered alongside the
+/// configured ones unless a profile of that name exists.
+pub const DEFAULT_PROFILE: &str = "default";
+/// Directories offered per profile in the launch picker.
+const MAX_DIRS: usize = 8;
+
+/// A named way of starting an agent in a new window.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProfile {
+    pub name: String,
+    /// Command to run; defaults to the agent's command, or `command` from the
+    /// config when no agent is set either.
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: IndexMap<String, String>,
+    /// Passed as `--model`.
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub agent: Option<Agent>,
+    /// Directory offered first for this profile. Supports `~/`.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub window_name: Option<String>,
+}
+
+impl LaunchProfile {
+    fn base_command(&self, config: &AppConfig) -> String {
+        match (&self.command, self.agent) {
+            (Some(command), _) => command.clone(),
+            (None, Some(agent)) => config.command_for(agent),
+            (None, None) => config.command.clone(),
+        }
+    }
+
+    /// Agent the profile starts, as given or inferred from its command.
+    pub fn agent(&self, config: &AppConfig) -> Agent {
+        self.agent
+            .unwrap_or_else(|| agent_for_command(&self.base_command(config)))
+    }
+
+    /// Shell command for `tmux new-window`. The command itself is used as
+    /// written; env values, the model and args are quoted.
+    pub fn shell_command(&self, config: &AppConfig) -> String {
+        let mut parts = Vec::new();
+        if !self.env.is_empty() {
+            parts.push("env".to_string());
+            for (key, value) in &self.env {
+                parts.push(format!("{}={}", key, quote(value)));
+            }
+        }
+        parts.push(self.base_command(config));
+        if let Some(ref model) = self.model {
+            parts.push("--model".to_string());
+            parts.push(quote(model));
+        }
+        parts.extend(self.args.iter().map(|arg| quote(arg)));
+        parts.join(" ")
+    }
+
+    /// Short description for the picker, e.g. `claude --model opus`.
+    pub fn summary(&self, config: &AppConfig) -> String {
+        let mut text = self.base_command(config);
+        if let Some(ref model) = self.model {
+            text.push_str(" --model ");
+            text.push_str(model);
+        }
+        for arg in &self.args {
+            text.push(' ');
+            text.push_str(arg);
+        }
+        text
+    }
+}
+
+/// Quotes `value` for the shell, leaving plain words as they are.
+pub(crate) fn quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// The configured profiles plus the default one, with `last_used` first.
+pub fn profiles(config: &AppConfig, last_used: Option<&str>) -> Vec<LaunchProfile> {
+    let mut profiles = config.launch_profiles.clone();
+    if !profiles.iter().any(|p| p.name == DEFAULT_PROFILE) {
+        profiles.push(LaunchProfile {
+            name: DEFAULT_PROFILE.to_string(),
+            command: Some(config.command.clone()),
+            ..Default::default()
+        });
+    }
+    if let Some(i) = profiles
+        .iter()
+        .position(|p| Some(p.name.as_str()) == last_used)
+    {
+        let profile = profiles.remove(i);
+        profiles.insert(0, profile);
+    }
+    profiles
+}
+
+/// One row of the launch picker. `cwd` is `None` when no directory is known,
+/// in which case the window opens in the selected pane's directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchEntry {
+    pub profile: usize,
+    pub cwd: Option<String>,
+}
+
+/// Picker rows: every profile in each of `dirs`, with a profile's own
+/// directory before the others.
+pub fn entries(profiles: &[LaunchProfile], dirs: &[String]) -> Vec<LaunchEntry> {
+    let mut entries = Vec::new();
+    for (i, profile) in profiles.iter().enumerate() {
+        let own = profile.cwd.as_deref().map(|cwd| {
+            crate::config::expand_tilde(cwd)
+                .to_string_lossy()
+                .into_owned()
+        });
+        let mut profile_dirs: Vec<&str> = own.iter().map(String::as_str).collect();
+        for dir in dirs {
+            if !profile_dirs.contains(&dir.as_str()) {
+                profile_dirs.push(dir);
+            }
+        }
+        if profile_dirs.is_empty() {
+            entries.push(LaunchEntry {
+                profile: i,
+                cwd: None,
+            });
+        }
+        for dir in profile_dirs.into_iter().take(MAX_DIRS) {
+            entries.push(LaunchEntry {
+                profile: i,
+                cwd: Some(dir.to_string()),
+            });
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str) -> LaunchProfile {
+        LaunchProfile {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builds_quoted_shell_command() {
+        let config = AppConfig {
+            command: "claude".to_string(),
+            ..AppConfig::default()
+        };
+        let mut env = IndexMap::new();
+        env.insert("MAX_THINKING_TOKENS".to_string(), "8000".to_string());
+        env.insert("NOTE".to_string(), "it's late".to_string());
+        let plan = LaunchProfile {
+            command: Some("claude".to_string()),
+            args: vec!["--permission-mode".to_string(), "plan".to_string()],
+            model: Some("opus".to_string()),
+            env,
+            ..profile("plan")
+        };
+        assert_eq!(
+            plan.shell_command(&config),
+            "env MAX_THINKING_TOKENS=8000 NOTE='it'\\''s late' claude --model opus --permission-mode plan"
+        );
+        let opencode = LaunchProfile {
+            command: Some("~/bin/opencode".to_string()),
+            ..profile("build")
+        };
+        assert_eq!(opencode.agent(&config), Agent::Opencode);
+    }
+
+    #[test]
+    fn last_used_profile_comes_first_with_its_own_dir() {
+        let mut build = profile("build");
+        build.cwd = Some("/srv/build".to_string());
+        let config = AppConfig {
+            command: "claude".to_string(),
+            launch_profiles: vec![profile("opus"), build],
+            ..AppConfig::default()
+        };
+        let profiles = super::profiles(&config, Some("build"));
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "opus", DEFAULT_PROFILE]);
+
+        let dirs = vec!["/code/api".to_string(), "/srv/build".to_string()];
+        let entries = entries(&profiles, &dirs);
+        let first: Vec<Option<&str>> = entries
+            .iter()
+            .filter(|e| e.profile == 0)
+            .map(|e| e.cwd.as_deref())
+            .collect();
+        assert_eq!(first, vec![Some("/srv/build"), Some("/code/api")]);
+        assert_eq!(entries.len(), 6);
+        assert_eq!(
+            super::entries(&profiles[1..2], &[]),
+            vec![LaunchEntry {
+                profile: 0,
+                cwd: None
+            }]
+        );
+    }
+}
@@ -7,22 +7,35 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 use std::io;
+use std::path::PathBuf;
 
 mod app;
 mod cache;
 mod config;
 mod copy_mode;
+mod diff;
 mod enrichment;
 mod filter_query;
+mod formatter;
+mod git;
+mod git_status;
+mod grouping;
+mod history;
 mod hook_write;
+mod launch;
 mod selection;
 mod session;
+mod slash_commands;
+mod snapshot;
 mod state;
+mod template;
 mod ui;
 
 mod pipe_pane;
 mod resize_pane;
 mod tmux;
+mod views;
+mod worktree;
 
 #[derive(clap::Subcommand)]
 enum Command {
@@ -34,6 +47,27 @@ enum Command {
         /// Hook event name: session-start, prompt-submit, stop, session-end
         event: String,
     },
+    /// Save or restore the agent fleet across tmux server restarts.
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum SnapshotAction {
+    /// Record every agent pane's tmux session, window, cwd, and session id.
+    Save {
+        /// Snapshot file (default: ~/.config/agent-dash/snapshot.json)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Recreate the recorded sessions and windows, resuming each agent.
+    Restore {
+        /// Snapshot file (default: ~/.config/agent-dash/snapshot.json)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
 }
 
 #[derive(Parser)]
@@ -65,6 +99,18 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if let Some(Command::Snapshot { action }) = cli.command {
+        let config = config::load_config(false);
+        return match action {
+            SnapshotAction::Save { file } => {
+                snapshot::save(&config, &file.unwrap_or_else(snapshot::default_path)).await
+            }
+            SnapshotAction::Restore { file } => {
+                snapshot::restore(&config, &file.unwrap_or_else(snapshot::default_path)).await
+            }
+        };
+    }
+
     // Terminal setup
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -8,11 +8,22 @@ pub enum Agent {
     Opencode,
 }
 
+impl Agent {
+    pub fn binary(self) -> &'static str {
+        match self {
+            Agent::Claude => "claude",
+            Agent::Opencode => "opencode",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SessionStatus {
     Active,
     Idle,
+    /// The agent process is gone but its pane is still open.
+    Exited,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,6 +33,55 @@ pub enum PromptState {
     Ask,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortMode {
+    /// Unread first, most recently finished on top.
+    #[default]
+    Priority,
+    /// Unread first, the agent that has been waiting the longest on top.
+    LongestWaiting,
+    /// Most recent status change on top, regardless of unread state.
+    StatusChanged,
+    /// Display name, then pane title.
+    Name,
+    /// tmux session, then window and pane.
+    TmuxSession,
+    Agent,
+    /// Model name; sessions without a known model last.
+    Model,
+    /// The order arranged by hand; new sessions and groups go last.
+    Manual,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Priority => SortMode::LongestWaiting,
+            SortMode::LongestWaiting => SortMode::StatusChanged,
+            SortMode::StatusChanged => SortMode::Name,
+            SortMode::Name => SortMode::TmuxSession,
+            SortMode::TmuxSession => SortMode::Agent,
+            SortMode::Agent => SortMode::Model,
+            SortMode::Model => SortMode::Manual,
+            SortMode::Manual => SortMode::Priority,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Priority => "priority",
+            SortMode::LongestWaiting => "waiting",
+            SortMode::StatusChanged => "recent",
+            SortMode::Name => "name",
+            SortMode::TmuxSession => "session",
+            SortMode::Agent => "agent",
+            SortMode::Model => "model",
+            SortMode::Manual => "manual",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentSession {
@@ -40,6 +100,33 @@ pub struct AgentSession {
     pub model: Option<String>,
     #[serde(default)]
     pub agent_role: Option<String>,
+    #[serde(default)]
+    pub window_name: Option<String>,
+    /// Command line the agent process runs, as reported by `ps`.
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+#[cfg(test)]
+impl AgentSession {
+    /// An idle Claude session in `main:0.0` titled after `pane_id`, for tests
+    /// to override fields on.
+    pub(crate) fn test(pane_id: &str) -> Self {
+        AgentSession {
+            pane_id: pane_id.to_string(),
+            pane_target: "main:0.0".to_string(),
+            title: pane_id.to_string(),
+            tmux_session_name: "main".to_string(),
+            status: SessionStatus::Idle,
+            agent: Agent::Claude,
+            session_id: None,
+            cwd: None,
+            model: None,
+            agent_role: None,
+            window_name: None,
+            command: None,
+        }
+    }
 }
 
 const BRAILLE_START: u32 = 0x2800;
@@ -95,10 +182,30 @@ pub fn detect_prompt_state(agent: Agent, visible_text: &str) -> PromptState {
     }
 }
 
+/// Hash of pane output used to notice whether an agent is still producing
+/// anything. Spinner/status lines and digits (elapsed timers, token counters)
+/// are ignored so a hung agent whose timer keeps ticking still looks unchanged.
+pub fn output_fingerprint(text: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for line in text.lines() {
+        if line.contains("esc to interrupt") || line.contains("esc interrupt") {
+            continue;
+        }
+        for ch in line.chars().filter(|c| !c.is_ascii_digit()) {
+            ch.hash(&mut hasher);
+        }
+        '\n'.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 // -- Session grouping --
 
 pub struct SessionGroup {
-    pub tmux_session_name: String,
+    /// tmux session name, or a prefixed key under other groupings
+    /// (see `grouping::group_key`).
+    pub key: String,
     pub sessions: Vec<AgentSession>,
 }
 
@@ -113,11 +220,14 @@ pub enum VisibleItem {
         in_hidden_section: bool,
     },
     GroupHeader {
+        group_key: String,
+        /// tmux session of the group's first session, for switching to it.
         tmux_session_name: String,
         display_name: String,
         session_count: usize,
         has_active: bool,
         has_unread: bool,
+        status_since: Option<u64>,
         is_collapsed: bool,
         in_subgroup: bool,
         in_hidden_section: bool,
@@ -129,7 +239,7 @@ pub enum VisibleItem {
         in_subgroup: bool,
     },
     GroupHiddenHeader {
-        tmux_session_name: String,
+        group_key: String,
         count: usize,
         is_collapsed: bool,
     },
@@ -137,26 +247,61 @@ pub enum VisibleItem {
         count: usize,
         is_collapsed: bool,
     },
+    RecentHeader {
+        count: usize,
+        is_collapsed: bool,
+    },
+    RecentSession {
+        entry: HistoryEntry,
+    },
 }
 
+use crate::history::HistoryEntry;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Group key of a session: its entry in `group_keys` (keyed by pane id), or
+/// its tmux session name.
+pub fn group_key<'a>(
+    group_keys: &'a HashMap<String, String>,
+    session: &'a AgentSession,
+) -> &'a str {
+    group_keys
+        .get(&session.pane_id)
+        .map(String::as_str)
+        .unwrap_or(&session.tmux_session_name)
+}
 
-pub fn group_sessions_by_name(sessions: &[AgentSession]) -> Vec<SessionGroup> {
+/// Groups sessions by `group_key`, in order of first appearance.
+pub fn group_sessions(
+    sessions: &[AgentSession],
+    group_keys: &HashMap<String, String>,
+) -> Vec<SessionGroup> {
     let mut map: indexmap::IndexMap<String, Vec<AgentSession>> = indexmap::IndexMap::new();
     for session in sessions {
-        map.entry(session.tmux_session_name.clone())
+        map.entry(group_key(group_keys, session).to_string())
             .or_default()
             .push(session.clone());
     }
     map.into_iter()
-        .map(|(tmux_session_name, sessions)| SessionGroup {
-            tmux_session_name,
-            sessions,
-        })
+        .map(|(key, sessions)| SessionGroup { key, sessions })
         .collect()
 }
 
+/// The session that stands for each tmux session when formatting its name:
+/// its first session with a known cwd, or its first session.
+pub fn tmux_session_leads(sessions: &[AgentSession]) -> Vec<&AgentSession> {
+    let mut leads: indexmap::IndexMap<&str, &AgentSession> = indexmap::IndexMap::new();
+    for session in sessions {
+        let lead = leads.entry(&session.tmux_session_name).or_insert(session);
+        if lead.cwd.is_none() && session.cwd.is_some() {
+            *lead = session;
+        }
+    }
+    leads.into_values().collect()
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn build_visible_items(
     groups: &[SessionGroup],
@@ -164,6 +309,8 @@ pub fn build_visible_items(
     collapsed_hidden_groups: &HashSet<String>,
     unread_pane_ids: &HashSet<String>,
     unread_order: &HashMap<String, u64>,
+    status_changed_at: &HashMap<String, u64>,
+    sort_mode: SortMode,
     prompt_states: &HashMap<String, PromptState>,
     display_name_map: &HashMap<String, String>,
     hidden_pane_ids: &HashSet<String>,
@@ -174,12 +321,29 @@ pub fn build_visible_items(
     group_name_separator: Option<&str>,
     collapsed_subgroups: &HashSet<String>,
     collapsed_hidden_subgroups: &HashSet<String>,
+    pinned_keys: &HashSet<String>,
+    pinned_groups: &HashSet<String>,
+    manual_order: &ManualOrder,
+    group_keys: &HashMap<String, String>,
+    session_names: &HashMap<String, String>,
 ) -> Vec<VisibleItem> {
+    let sort_keys = SortKeys {
+        unread_pane_ids,
+        unread_order,
+        status_changed_at,
+        prompt_states,
+        display_name_map,
+        pinned_keys,
+        pinned_groups,
+        manual_order,
+        group_keys,
+        session_names,
+    };
     let mut items = Vec::new();
     let mut visible_groups: Vec<(&SessionGroup, Vec<&AgentSession>)> = Vec::new();
     let mut all_hidden_groups: Vec<&SessionGroup> = Vec::new();
     for group in groups {
-        if !include_hidden && hidden_groups.contains(&group.tmux_session_name) {
+        if !include_hidden && hidden_groups.contains(&group.key) {
             continue;
         }
         let mut visible_sessions: Vec<&AgentSession> = group
@@ -191,51 +355,25 @@ pub fn build_visible_items(
             all_hidden_groups.push(group);
             continue;
         }
-        visible_sessions.sort_by(|a, b| {
-            let tier_a = session_priority_tier(a, unread_pane_ids, prompt_states);
-            let tier_b = session_priority_tier(b, unread_pane_ids, prompt_states);
-            if tier_a != tier_b {
-                return tier_a.cmp(&tier_b);
-            }
-            if tier_a <= 1 {
-                let order_a = unread_order.get(&a.pane_id).copied().unwrap_or(0);
-                let order_b = unread_order.get(&b.pane_id).copied().unwrap_or(0);
-                return order_b.cmp(&order_a);
-            }
-            Ordering::Equal
-        });
+        visible_sessions.sort_by(|a, b| sort_keys.compare(a, b, sort_mode));
         visible_groups.push((group, visible_sessions));
     }
+    // Each group's sessions are already sorted, so comparing the leading
+    // members orders groups by their most urgent (or first-sorting) session.
     visible_groups.sort_by(|a, b| {
-        let tier_a =
-            a.1.iter()
-                .map(|s| session_priority_tier(s, unread_pane_ids, prompt_states))
-                .min()
-                .unwrap_or(u8::MAX);
-        let tier_b =
-            b.1.iter()
-                .map(|s| session_priority_tier(s, unread_pane_ids, prompt_states))
-                .min()
-                .unwrap_or(u8::MAX);
-        if tier_a != tier_b {
-            return tier_a.cmp(&tier_b);
-        }
-        if tier_a <= 1 {
-            let order_a =
-                a.1.iter()
-                    .filter(|s| unread_pane_ids.contains(&s.pane_id))
-                    .map(|s| unread_order.get(&s.pane_id).copied().unwrap_or(0))
-                    .max()
-                    .unwrap_or(0);
-            let order_b =
-                b.1.iter()
-                    .filter(|s| unread_pane_ids.contains(&s.pane_id))
-                    .map(|s| unread_order.get(&s.pane_id).copied().unwrap_or(0))
-                    .max()
-                    .unwrap_or(0);
-            return order_b.cmp(&order_a);
+        if sort_mode == SortMode::Manual {
+            let (lead_a, lead_b) = (a.1[0], b.1[0]);
+            sort_keys
+                .is_pinned(lead_b)
+                .cmp(&sort_keys.is_pinned(lead_a))
+                .then_with(|| {
+                    manual_order
+                        .group_rank(&a.0.key)
+                        .cmp(&manual_order.group_rank(&b.0.key))
+                })
+        } else {
+            sort_keys.compare(a.1[0], b.1[0], sort_mode)
         }
-        Ordering::Equal
     });
 
     emit_groups_with_subgrouping(
@@ -243,6 +381,7 @@ pub fn build_visible_items(
         &mut items,
         collapsed_groups,
         unread_pane_ids,
+        status_changed_at,
         display_name_map,
         hidden_pane_ids,
         group_hidden_collapsed,
@@ -255,7 +394,7 @@ pub fn build_visible_items(
     if !include_hidden {
         let mut hidden_groups_data: Vec<(&SessionGroup, Vec<&AgentSession>)> = Vec::new();
         for group in groups {
-            if !hidden_groups.contains(&group.tmux_session_name) {
+            if !hidden_groups.contains(&group.key) {
                 continue;
             }
             let sessions: Vec<&AgentSession> = group.sessions.iter().collect();
@@ -274,6 +413,7 @@ pub fn build_visible_items(
                 &mut hidden_items,
                 collapsed_hidden_groups,
                 unread_pane_ids,
+                status_changed_at,
                 display_name_map,
                 hidden_pane_ids,
                 group_hidden_collapsed,
@@ -301,6 +441,7 @@ fn emit_groups_with_subgrouping<'a>(
     items: &mut Vec<VisibleItem>,
     collapsed_groups: &HashSet<String>,
     unread_pane_ids: &HashSet<String>,
+    status_changed_at: &HashMap<String, u64>,
     display_name_map: &HashMap<String, String>,
     hidden_pane_ids: &HashSet<String>,
     group_hidden_collapsed: &HashSet<String>,
@@ -314,9 +455,9 @@ fn emit_groups_with_subgrouping<'a>(
             indexmap::IndexMap::new();
         for (group, sessions) in groups {
             let display_name = display_name_map
-                .get(&group.tmux_session_name)
+                .get(&group.key)
                 .map(String::as_str)
-                .unwrap_or(&group.tmux_session_name);
+                .unwrap_or(&group.key);
             let prefix = display_name
                 .split_once(sep)
                 .map(|(p, _)| p.to_string())
@@ -330,9 +471,9 @@ fn emit_groups_with_subgrouping<'a>(
         for (prefix, sub_groups) in prefix_map {
             let any_has_sep = sub_groups.iter().any(|(group, _)| {
                 let dn = display_name_map
-                    .get(&group.tmux_session_name)
+                    .get(&group.key)
                     .map(String::as_str)
-                    .unwrap_or(&group.tmux_session_name);
+                    .unwrap_or(&group.key);
                 dn.contains(sep)
             });
 
@@ -358,9 +499,9 @@ fn emit_groups_with_subgrouping<'a>(
                 if !is_collapsed {
                     for (group, sessions) in sub_groups {
                         let display_name = display_name_map
-                            .get(&group.tmux_session_name)
+                            .get(&group.key)
                             .map(String::as_str)
-                            .unwrap_or(&group.tmux_session_name);
+                            .unwrap_or(&group.key);
                         let header_display = display_name
                             .split_once(sep)
                             .map(|(_, suffix)| suffix.to_string())
@@ -373,6 +514,7 @@ fn emit_groups_with_subgrouping<'a>(
                             items,
                             collapsed_groups,
                             unread_pane_ids,
+                            status_changed_at,
                             hidden_pane_ids,
                             group_hidden_collapsed,
                             true,
@@ -384,9 +526,9 @@ fn emit_groups_with_subgrouping<'a>(
             } else {
                 for (group, sessions) in sub_groups {
                     let display_name = display_name_map
-                        .get(&group.tmux_session_name)
+                        .get(&group.key)
                         .map(String::as_str)
-                        .unwrap_or(&group.tmux_session_name);
+                        .unwrap_or(&group.key);
                     emit_single_group(
                         group,
                         display_name,
@@ -395,6 +537,7 @@ fn emit_groups_with_subgrouping<'a>(
                         items,
                         collapsed_groups,
                         unread_pane_ids,
+                        status_changed_at,
                         hidden_pane_ids,
                         group_hidden_collapsed,
                         false,
@@ -407,9 +550,9 @@ fn emit_groups_with_subgrouping<'a>(
     } else {
         for (group, sessions) in groups {
             let display_name = display_name_map
-                .get(&group.tmux_session_name)
+                .get(&group.key)
                 .map(String::as_str)
-                .unwrap_or(&group.tmux_session_name);
+                .unwrap_or(&group.key);
             emit_single_group(
                 group,
                 display_name,
@@ -418,6 +561,7 @@ fn emit_groups_with_subgrouping<'a>(
                 items,
                 collapsed_groups,
                 unread_pane_ids,
+                status_changed_at,
                 hidden_pane_ids,
                 group_hidden_collapsed,
                 false,
@@ -437,6 +581,7 @@ fn emit_single_group<'a>(
     items: &mut Vec<VisibleItem>,
     collapsed_groups: &HashSet<String>,
     unread_pane_ids: &HashSet<String>,
+    status_changed_at: &HashMap<String, u64>,
     hidden_pane_ids: &HashSet<String>,
     group_hidden_collapsed: &HashSet<String>,
     in_subgroup: bool,
@@ -447,13 +592,16 @@ fn emit_single_group<'a>(
     let has_unread = sessions
         .iter()
         .any(|s| unread_pane_ids.contains(&s.pane_id));
-    let is_collapsed = collapsed_groups.contains(&group.tmux_session_name);
+    let is_collapsed = collapsed_groups.contains(&group.key);
+    let status_since = group_status_since(&sessions, status_changed_at);
     items.push(VisibleItem::GroupHeader {
-        tmux_session_name: group.tmux_session_name.clone(),
+        group_key: group.key.clone(),
+        tmux_session_name: sessions[0].tmux_session_name.clone(),
         display_name: header_display.to_string(),
         session_count: sessions.len(),
         has_active,
         has_unread,
+        status_since,
         is_collapsed,
         in_subgroup,
         in_hidden_section,
@@ -474,10 +622,9 @@ fn emit_single_group<'a>(
                 .filter(|s| hidden_pane_ids.contains(&s.pane_id))
                 .collect();
             if !hidden_in_group.is_empty() {
-                let is_section_collapsed =
-                    group_hidden_collapsed.contains(&group.tmux_session_name);
+                let is_section_collapsed = group_hidden_collapsed.contains(&group.key);
                 items.push(VisibleItem::GroupHiddenHeader {
-                    tmux_session_name: group.tmux_session_name.clone(),
+                    group_key: group.key.clone(),
                     count: hidden_in_group.len(),
                     is_collapsed: is_section_collapsed,
                 });
@@ -510,16 +657,16 @@ pub fn resolve_selected_index(
                     return found;
                 }
             }
-            VisibleItem::GroupHeader { tmux_session_name, .. } => {
+            VisibleItem::GroupHeader { group_key, .. } => {
                 if let Some(found) = new_items.iter().position(|item| {
-                    matches!(item, VisibleItem::GroupHeader { tmux_session_name: name, .. } if name == tmux_session_name)
+                    matches!(item, VisibleItem::GroupHeader { group_key: key, .. } if key == group_key)
                 }) {
                     return found;
                 }
             }
-            VisibleItem::GroupHiddenHeader { tmux_session_name, .. } => {
+            VisibleItem::GroupHiddenHeader { group_key, .. } => {
                 if let Some(found) = new_items.iter().position(|item| {
-                    matches!(item, VisibleItem::GroupHiddenHeader { tmux_session_name: name, .. } if name == tmux_session_name)
+                    matches!(item, VisibleItem::GroupHiddenHeader { group_key: key, .. } if key == group_key)
                 }) {
                     return found;
                 }
@@ -538,6 +685,21 @@ pub fn resolve_selected_index(
                     return found;
                 }
             }
+            VisibleItem::RecentHeader { .. } => {
+                if let Some(found) = new_items
+                    .iter()
+                    .position(|item| matches!(item, VisibleItem::RecentHeader { .. }))
+                {
+                    return found;
+                }
+            }
+            VisibleItem::RecentSession { entry } => {
+                if let Some(found) = new_items.iter().position(|item| {
+                    matches!(item, VisibleItem::RecentSession { entry: e } if e.session_id == entry.session_id)
+                }) {
+                    return found;
+                }
+            }
         }
     }
     if new_items.is_empty() {
@@ -565,6 +727,194 @@ pub fn auto_select_index(
     0
 }
 
+/// Session state the sort modes order by.
+struct SortKeys<'a> {
+    unread_pane_ids: &'a HashSet<String>,
+    unread_order: &'a HashMap<String, u64>,
+    status_changed_at: &'a HashMap<String, u64>,
+    prompt_states: &'a HashMap<String, PromptState>,
+    display_name_map: &'a HashMap<String, String>,
+    pinned_keys: &'a HashSet<String>,
+    pinned_groups: &'a HashSet<String>,
+    manual_order: &'a ManualOrder,
+    group_keys: &'a HashMap<String, String>,
+    /// Names given with rename, keyed by `pin_key`.
+    session_names: &'a HashMap<String, String>,
+}
+
+impl SortKeys<'_> {
+    fn is_pinned(&self, session: &AgentSession) -> bool {
+        self.pinned_keys.contains(pin_key(session))
+            || self
+                .pinned_groups
+                .contains(group_key(self.group_keys, session))
+    }
+
+    /// Full ordering of two sessions under `sort_mode`. Pinned sessions (and
+    /// sessions in pinned groups) come first. Attribute-based modes fall back
+    /// to urgency for ties; manual keeps the input order.
+    fn compare(&self, a: &AgentSession, b: &AgentSession, sort_mode: SortMode) -> Ordering {
+        self.is_pinned(b)
+            .cmp(&self.is_pinned(a))
+            .then_with(|| self.compare_unpinned(a, b, sort_mode))
+    }
+
+    fn compare_unpinned(
+        &self,
+        a: &AgentSession,
+        b: &AgentSession,
+        sort_mode: SortMode,
+    ) -> Ordering {
+        match sort_mode {
+            SortMode::Priority | SortMode::LongestWaiting => self.compare_urgency(a, b, sort_mode),
+            SortMode::StatusChanged => {
+                let since = |s: &AgentSession| self.status_changed_at.get(&s.pane_id).copied();
+                since(b).cmp(&since(a))
+            }
+            SortMode::Name => self
+                .name_key(a)
+                .cmp(&self.name_key(b))
+                .then_with(|| self.compare_urgency(a, b, SortMode::Priority)),
+            SortMode::TmuxSession => a
+                .tmux_session_name
+                .cmp(&b.tmux_session_name)
+                .then_with(|| pane_position(&a.pane_target).cmp(&pane_position(&b.pane_target))),
+            SortMode::Agent => a
+                .agent
+                .binary()
+                .cmp(b.agent.binary())
+                .then_with(|| self.compare_urgency(a, b, SortMode::Priority)),
+            SortMode::Model => {
+                let model = |s: &AgentSession| s.model.as_deref().map(str::to_lowercase);
+                match (model(a), model(b)) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+                .then_with(|| self.compare_urgency(a, b, SortMode::Priority))
+            }
+            SortMode::Manual => self
+                .manual_order
+                .session_rank(pin_key(a))
+                .cmp(&self.manual_order.session_rank(pin_key(b))),
+        }
+    }
+
+    /// Unread first (plain before plan/ask), then busy, then idle. Unread
+    /// sessions of the same tier are ordered by `sort_mode`.
+    fn compare_urgency(&self, a: &AgentSession, b: &AgentSession, sort_mode: SortMode) -> Ordering {
+        let tier_a = session_priority_tier(a, self.unread_pane_ids, self.prompt_states);
+        let tier_b = session_priority_tier(b, self.unread_pane_ids, self.prompt_states);
+        if tier_a != tier_b {
+            return tier_a.cmp(&tier_b);
+        }
+        if tier_a > 1 {
+            return Ordering::Equal;
+        }
+        if sort_mode == SortMode::LongestWaiting {
+            // Earlier transition = waiting longer = first
+            let since = |s: &AgentSession| {
+                self.status_changed_at
+                    .get(&s.pane_id)
+                    .copied()
+                    .unwrap_or(u64::MAX)
+            };
+            since(a).cmp(&since(b))
+        } else {
+            // Higher counter = more recent = first
+            let order = |s: &AgentSession| self.unread_order.get(&s.pane_id).copied().unwrap_or(0);
+            order(b).cmp(&order(a))
+        }
+    }
+
+    /// The group label shown for `session`, then its title. The tree view's
+    /// labels are keyed by group key, the flat view's by tmux session name.
+    fn name_key(&self, session: &AgentSession) -> (String, String) {
+        let display_name = self
+            .display_name_map
+            .get(group_key(self.group_keys, session))
+            .or_else(|| self.display_name_map.get(&session.tmux_session_name))
+            .unwrap_or(&session.tmux_session_name);
+        let title = self
+            .session_names
+            .get(pin_key(session))
+            .unwrap_or(&session.title);
+        (display_name.to_lowercase(), title.to_lowercase())
+    }
+}
+
+/// Hand-arranged order used by `SortMode::Manual`. Groups are grouping keys
+/// (see `group_key`); sessions are `pin_key`s. Anything not listed sorts after
+/// the listed entries, in discovery order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct ManualOrder {
+    pub groups: Vec<String>,
+    pub sessions: Vec<String>,
+}
+
+impl ManualOrder {
+    fn group_rank(&self, name: &str) -> usize {
+        rank(&self.groups, name)
+    }
+
+    fn session_rank(&self, key: &str) -> usize {
+        rank(&self.sessions, key)
+    }
+}
+
+fn rank(order: &[String], key: &str) -> usize {
+    order.iter().position(|k| k == key).unwrap_or(usize::MAX)
+}
+
+/// `shown` followed by the entries of `previous` it doesn't contain, so
+/// entries that are currently filtered out keep their relative place.
+pub fn merge_order(shown: Vec<String>, previous: &[String]) -> Vec<String> {
+    let mut order = shown;
+    for key in previous {
+        if !order.contains(key) {
+            order.push(key.clone());
+        }
+    }
+    order
+}
+
+/// Key pins are stored under: the agent's session id when known, so a pin
+/// survives the pane being replaced, otherwise the pane id.
+pub fn pin_key(session: &AgentSession) -> &str {
+    session.session_id.as_deref().unwrap_or(&session.pane_id)
+}
+
+/// Window and pane index from a `session:window.pane` target, so that window
+/// 10 sorts after window 2.
+fn pane_position(pane_target: &str) -> (u32, u32) {
+    let Some((_, rest)) = pane_target.rsplit_once(':') else {
+        return (0, 0);
+    };
+    let mut parts = rest.split('.').map(|p| p.parse().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// Timestamp shown on a group header: how long the longest-running busy member
+/// has been busy, otherwise when the most recent member went idle.
+fn group_status_since(
+    sessions: &[&AgentSession],
+    status_changed_at: &HashMap<String, u64>,
+) -> Option<u64> {
+    let since = |s: &&&AgentSession| status_changed_at.get(&s.pane_id).copied();
+    let active: Vec<&&AgentSession> = sessions
+        .iter()
+        .filter(|s| s.status == SessionStatus::Active)
+        .collect();
+    if active.is_empty() {
+        sessions.iter().filter_map(|s| since(&s)).max()
+    } else {
+        active.iter().filter_map(since).min()
+    }
+}
+
 fn session_priority_tier(
     session: &AgentSession,
     unread_pane_ids: &HashSet<String>,
@@ -592,18 +942,37 @@ pub fn build_flat_visible_items(
     sessions: &[AgentSession],
     unread_pane_ids: &HashSet<String>,
     unread_order: &HashMap<String, u64>,
+    status_changed_at: &HashMap<String, u64>,
+    sort_mode: SortMode,
     prompt_states: &HashMap<String, PromptState>,
     display_name_map: &HashMap<String, String>,
     hidden_pane_ids: &HashSet<String>,
     hidden_groups: &HashSet<String>,
     hidden_section_collapsed: bool,
     include_hidden: bool,
+    pinned_keys: &HashSet<String>,
+    pinned_groups: &HashSet<String>,
+    manual_order: &ManualOrder,
+    group_keys: &HashMap<String, String>,
+    session_names: &HashMap<String, String>,
 ) -> Vec<VisibleItem> {
+    let sort_keys = SortKeys {
+        unread_pane_ids,
+        unread_order,
+        status_changed_at,
+        prompt_states,
+        display_name_map,
+        pinned_keys,
+        pinned_groups,
+        manual_order,
+        group_keys,
+        session_names,
+    };
     let (hidden_sessions, visible_sessions): (Vec<&AgentSession>, Vec<&AgentSession>) =
         sessions.iter().partition(|s| {
             !include_hidden
                 && (hidden_pane_ids.contains(&s.pane_id)
-                    || hidden_groups.contains(&s.tmux_session_name))
+                    || hidden_groups.contains(group_key(group_keys, s)))
         });
 
     let mut items: Vec<VisibleItem> = visible_sessions
@@ -629,31 +998,21 @@ pub fn build_flat_visible_items(
             VisibleItem::SubgroupHeader { .. }
             | VisibleItem::GroupHeader { .. }
             | VisibleItem::GroupHiddenHeader { .. }
-            | VisibleItem::HiddenHeader { .. } => return Ordering::Equal,
+            | VisibleItem::HiddenHeader { .. }
+            | VisibleItem::RecentHeader { .. }
+            | VisibleItem::RecentSession { .. } => return Ordering::Equal,
         };
         let session_b = match b {
             VisibleItem::Session { session, .. } => session,
             VisibleItem::SubgroupHeader { .. }
             | VisibleItem::GroupHeader { .. }
             | VisibleItem::GroupHiddenHeader { .. }
-            | VisibleItem::HiddenHeader { .. } => return Ordering::Equal,
+            | VisibleItem::HiddenHeader { .. }
+            | VisibleItem::RecentHeader { .. }
+            | VisibleItem::RecentSession { .. } => return Ordering::Equal,
         };
 
-        let tier_a = session_priority_tier(session_a, unread_pane_ids, prompt_states);
-        let tier_b = session_priority_tier(session_b, unread_pane_ids, prompt_states);
-
-        if tier_a != tier_b {
-            return tier_a.cmp(&tier_b);
-        }
-
-        // Within tiers 0 and 1, sort by unread_order descending (higher counter = more recent = first)
-        if tier_a <= 1 {
-            let order_a = unread_order.get(&session_a.pane_id).copied().unwrap_or(0);
-            let order_b = unread_order.get(&session_b.pane_id).copied().unwrap_or(0);
-            return order_b.cmp(&order_a);
-        }
-
-        Ordering::Equal
+        sort_keys.compare(session_a, session_b, sort_mode)
     });
 
     if !hidden_sessions.is_empty() {
@@ -680,3 +1039,321 @@ pub fn build_flat_visible_items(
 
     items
 }
+
+/// Appends the "Recently closed" section. Entries whose session is running
+/// again are skipped.
+pub fn append_recent_items(
+    items: &mut Vec<VisibleItem>,
+    history: &[HistoryEntry],
+    sessions: &[AgentSession],
+    is_collapsed: bool,
+) {
+    let entries: Vec<&HistoryEntry> = history
+        .iter()
+        .filter(|e| {
+            !sessions.iter().any(|s| {
+                s.status != SessionStatus::Exited
+                    && s.session_id.as_deref() == Some(e.session_id.as_str())
+            })
+        })
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+    items.push(VisibleItem::RecentHeader {
+        count: entries.len(),
+        is_collapsed,
+    });
+    if !is_collapsed {
+        items.extend(entries.into_iter().map(|entry| VisibleItem::RecentSession {
+            entry: entry.clone(),
+        }));
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Compact elapsed time for list rows: `45s`, `12m`, `3h`, `2d`.
+pub fn format_elapsed(since_ms: u64, now_ms: u64) -> String {
+    let secs = now_ms.saturating_sub(since_ms) / 1000;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 60 * 60 {
+        format!("{}m", secs / 60)
+    } else if secs < 24 * 60 * 60 {
+        format!("{}h", secs / (60 * 60))
+    } else {
+        format!("{}d", secs / (24 * 60 * 60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(pane_id: &str, status: SessionStatus) -> AgentSession {
+        AgentSession {
+            status,
+            ..AgentSession::test(pane_id)
+        }
+    }
+
+    fn pane_ids(items: &[VisibleItem]) -> Vec<&str> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                VisibleItem::Session { session, .. } => Some(session.pane_id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn format_elapsed_units() {
+        assert_eq!(format_elapsed(0, 45_000), "45s");
+        assert_eq!(format_elapsed(0, 12 * 60_000), "12m");
+        assert_eq!(format_elapsed(0, 3 * 3_600_000 + 59_000), "3h");
+        assert_eq!(format_elapsed(0, 2 * 86_400_000), "2d");
+        assert_eq!(format_elapsed(10_000, 0), "0s");
+    }
+
+    #[test]
+    fn output_fingerprint_ignores_timers_and_spinner_line() {
+        let a = "Running tests\n✻ Thinking… (12s · esc to interrupt)\nelapsed 12s";
+        let b = "Running tests\n✢ Pondering… (95s · esc to interrupt)\nelapsed 95s";
+        assert_eq!(output_fingerprint(a), output_fingerprint(b));
+        let c = "Running tests\nall passed\n✻ Thinking… (12s · esc to interrupt)";
+        assert_ne!(output_fingerprint(a), output_fingerprint(c));
+    }
+
+    #[test]
+    fn longest_waiting_puts_oldest_unread_first() {
+        let sessions = vec![
+            session("%1", SessionStatus::Idle),
+            session("%2", SessionStatus::Idle),
+            session("%3", SessionStatus::Active),
+        ];
+        let unread: HashSet<String> = ["%1", "%2"].iter().map(|s| s.to_string()).collect();
+        let unread_order = HashMap::from([("%1".to_string(), 1), ("%2".to_string(), 2)]);
+        let changed = HashMap::from([
+            ("%1".to_string(), 1_000),
+            ("%2".to_string(), 2_000),
+            ("%3".to_string(), 500),
+        ]);
+        let build = |mode| {
+            build_flat_visible_items(
+                &sessions,
+                &unread,
+                &unread_order,
+                &changed,
+                mode,
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashSet::new(),
+                &HashSet::new(),
+                true,
+                false,
+                &HashSet::new(),
+                &HashSet::new(),
+                &ManualOrder::default(),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+        };
+        assert_eq!(pane_ids(&build(SortMode::Priority)), ["%2", "%1", "%3"]);
+        assert_eq!(
+            pane_ids(&build(SortMode::LongestWaiting)),
+            ["%1", "%2", "%3"]
+        );
+    }
+
+    #[test]
+    fn attribute_modes_sort_flat_list() {
+        let mut sessions = vec![
+            session("%1", SessionStatus::Idle),
+            session("%2", SessionStatus::Idle),
+            session("%3", SessionStatus::Idle),
+        ];
+        sessions[0].pane_target = "main:10.0".to_string();
+        sessions[0].title = "beta".to_string();
+        sessions[1].pane_target = "main:2.0".to_string();
+        sessions[1].title = "Alpha".to_string();
+        sessions[1].model = Some("sonnet".to_string());
+        sessions[2].pane_target = "main:3.0".to_string();
+        sessions[2].title = "gamma".to_string();
+        sessions[2].model = Some("opus".to_string());
+        let build = |mode| {
+            build_flat_visible_items(
+                &sessions,
+                &HashSet::new(),
+                &HashMap::new(),
+                &HashMap::new(),
+                mode,
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashSet::new(),
+                &HashSet::new(),
+                true,
+                false,
+                &HashSet::new(),
+                &HashSet::new(),
+                &ManualOrder::default(),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+        };
+        assert_eq!(pane_ids(&build(SortMode::Name)), ["%2", "%1", "%3"]);
+        assert_eq!(pane_ids(&build(SortMode::TmuxSession)), ["%2", "%3", "%1"]);
+        assert_eq!(pane_ids(&build(SortMode::Model)), ["%3", "%2", "%1"]);
+        assert_eq!(pane_ids(&build(SortMode::Manual)), ["%1", "%2", "%3"]);
+    }
+
+    #[test]
+    fn name_sort_uses_renamed_titles() {
+        let mut sessions = vec![
+            session("%1", SessionStatus::Idle),
+            session("%2", SessionStatus::Idle),
+        ];
+        sessions[0].title = "beta".to_string();
+        sessions[1].title = "alpha".to_string();
+        let names = HashMap::from([("%1".to_string(), "aardvark".to_string())]);
+        let items = build_flat_visible_items(
+            &sessions,
+            &HashSet::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            SortMode::Name,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashSet::new(),
+            &HashSet::new(),
+            true,
+            false,
+            &HashSet::new(),
+            &HashSet::new(),
+            &ManualOrder::default(),
+            &HashMap::new(),
+            &names,
+        );
+        assert_eq!(pane_ids(&items), ["%1", "%2"]);
+    }
+
+    #[test]
+    fn manual_order_ranks_listed_entries_first() {
+        let sessions = vec![
+            session("%1", SessionStatus::Idle),
+            session("%2", SessionStatus::Idle),
+            session("%3", SessionStatus::Idle),
+        ];
+        let order = ManualOrder {
+            groups: Vec::new(),
+            sessions: vec!["%3".to_string(), "%1".to_string()],
+        };
+        let items = build_flat_visible_items(
+            &sessions,
+            &HashSet::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            SortMode::Manual,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashSet::new(),
+            &HashSet::new(),
+            true,
+            false,
+            &HashSet::new(),
+            &HashSet::new(),
+            &order,
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+        assert_eq!(pane_ids(&items), ["%3", "%1", "%2"]);
+        let merged = merge_order(
+            vec!["b".to_string(), "a".to_string()],
+            &["a".to_string(), "c".to_string()],
+        );
+        assert_eq!(merged, ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn tree_orders_groups_by_most_urgent_member() {
+        let mut sessions = vec![
+            session("%1", SessionStatus::Idle),
+            session("%2", SessionStatus::Active),
+            session("%3", SessionStatus::Idle),
+            session("%4", SessionStatus::Idle),
+        ];
+        sessions[2].tmux_session_name = "other".to_string();
+        sessions[3].tmux_session_name = "other".to_string();
+        let unread: HashSet<String> = ["%4".to_string()].into();
+        let groups = group_sessions(&sessions, &HashMap::new());
+        let items = build_visible_items(
+            &groups,
+            &HashSet::new(),
+            &HashSet::new(),
+            &unread,
+            &HashMap::new(),
+            &HashMap::new(),
+            SortMode::Priority,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashSet::new(),
+            &HashSet::new(),
+            true,
+            &HashSet::new(),
+            false,
+            None,
+            &HashSet::new(),
+            &HashSet::new(),
+            &HashSet::new(),
+            &HashSet::new(),
+            &ManualOrder::default(),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+        assert_eq!(pane_ids(&items), ["%4", "%3", "%2", "%1"]);
+    }
+
+    #[test]
+    fn pins_float_above_unread() {
+        let mut sessions = vec![
+            session("%1", SessionStatus::Idle),
+            session("%2", SessionStatus::Idle),
+            session("%3", SessionStatus::Idle),
+        ];
+        sessions[1].session_id = Some("main-agent".to_string());
+        sessions[2].tmux_session_name = "ops".to_string();
+        let unread: HashSet<String> = ["%1".to_string()].into();
+        let build = |keys: &HashSet<String>, groups: &HashSet<String>| {
+            build_flat_visible_items(
+                &sessions,
+                &unread,
+                &HashMap::new(),
+                &HashMap::new(),
+                SortMode::Priority,
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashSet::new(),
+                &HashSet::new(),
+                true,
+                false,
+                keys,
+                groups,
+                &ManualOrder::default(),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+        };
+        let none = HashSet::new();
+        assert_eq!(pane_ids(&build(&none, &none)), ["%1", "%2", "%3"]);
+        let keys: HashSet<String> = ["main-agent".to_string()].into();
+        assert_eq!(pane_ids(&build(&keys, &none)), ["%2", "%1", "%3"]);
+        let groups: HashSet<String> = ["ops".to_string()].into();
+        assert_eq!(pane_ids(&build(&none, &groups)), ["%3", "%1", "%2"]);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::session::Agent;
+use serde::Deserialize;
+
+/// A slash command offered by the command palette.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommand {
+    /// Command as typed, e.g. `/model <name>`. A `<placeholder>` means the
+    /// command needs an argument before it can be sent.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Restricts the command to one agent; `None` offers it for every agent.
+    #[serde(default)]
+    pub agent: Option<Agent>,
+}
+
+impl SlashCommand {
+    fn builtin(name: &str, description: &str, agent: Agent) -> Self {
+        SlashCommand {
+            name: name.to_string(),
+            description: description.to_string(),
+            agent: Some(agent),
+        }
+    }
+
+    /// Text to prefill when the command takes an argument, or `None` if it can
+    /// be sent as is.
+    pub fn argument_prefix(&self) -> Option<&str> {
+        let start = self.name.find('<')?;
+        Some(&self.name[..start])
+    }
+}
+
+fn builtin_commands(agent: Agent) -> Vec<SlashCommand> {
+    let commands: &[(&str, &str)] = match agent {
+        Agent::Claude => &[
+            ("/compact", "Compact the conversation history"),
+            ("/clear", "Clear the conversation"),
+            ("/model <name>", "Switch model"),
+            ("/cost", "Show token usage and cost"),
+            ("/context", "Show context window usage"),
+            ("/status", "Show session status"),
+            ("/memory", "Edit memory files"),
+            ("/review", "Review a pull request"),
+            ("/init", "Create a CLAUDE.md for the project"),
+        ],
+        Agent::Opencode => &[
+            ("/compact", "Summarize the session"),
+            ("/new", "Start a new session"),
+            ("/models", "List available models"),
+            ("/sessions", "List sessions"),
+            ("/undo", "Undo the last message"),
+            ("/redo", "Redo the last undone message"),
+            ("/share", "Share the session"),
+            ("/init", "Create an AGENTS.md for the project"),
+        ],
+    };
+    commands
+        .iter()
+        .map(|(name, description)| SlashCommand::builtin(name, description, agent))
+        .collect()
+}
+
+/// Commands available for `agent`: configured ones first, then built-ins not
+/// overridden by a configured command of the same name.
+pub fn commands_for(agent: Agent, custom: &[SlashCommand]) -> Vec<SlashCommand> {
+    let mut commands: Vec<SlashCommand> = custom
+        .iter()
+        .filter(|c| c.agent.is_none_or(|a| a == agent))
+        .cloned()
+        .collect();
+    for builtin in builtin_commands(agent) {
+        if !commands.iter().any(|c| c.name == builtin.name) {
+            commands.push(builtin);
+        }
+    }
+    commands
+}
+
+/// Indices of `commands` matching `query`, best match first. An empty query
+/// keeps the original order.
+pub fn filter(commands: &[SlashCommand], query: &str) -> Vec<usize> {
+    let haystacks: Vec<String> = commands
+        .iter()
+        .map(|command| format!("{} {}", command.name, command.description))
+        .collect();
+    fuzzy_rank(&haystacks, query)
+}
+
+/// Indices of `haystacks` fuzzy-matching `query`, best match first. An empty
+/// query keeps the original order.
+pub fn fuzzy_rank(haystacks: &[String], query: &str) -> Vec<usize> {
+    use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+    use nucleo_matcher::{Matcher, Utf32Str};
+
+    if query.trim().is_empty() {
+        return (0..haystacks.len()).collect();
+    }
+    let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
+    let pattern = Pattern::parse(query, CaseMatching::Smart, Normalization::Smart);
+    let mut scored: Vec<(u32, usize)> = haystacks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, haystack)| {
+            let mut buf = Vec::new();
+            let score = pattern.score(Utf32Str::new(haystack, &mut buf), &mut matcher)?;
+            Some((score, i))
+        })
+        .collect();
+    scored.sort_by_key(|(score, i)| (std::cmp::Reverse(*score), *i));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(name: &str, agent: Option<Agent>) -> SlashCommand {
+        SlashCommand {
+            name: name.to_string(),
+            description: "custom".to_string(),
+            agent,
+        }
+    }
+
+    #[test]
+    fn custom_commands_come_first_and_override_builtins() {
+        let config = vec![
+            custom("/deploy", None),
+            custom("/compact", Some(Agent::Claude)),
+            custom("/oc-only", Some(Agent::Opencode)),
+        ];
+        let commands = commands_for(Agent::Claude, &config);
+        assert_eq!(commands[0].name, "/deploy");
+        assert_eq!(commands[1].description, "custom");
+        assert_eq!(commands.iter().filter(|c| c.name == "/compact").count(), 1);
+        assert!(!commands.iter().any(|c| c.name == "/oc-only"));
+    }
+
+    #[test]
+    fn filter_ranks_matches() {
+        let commands = commands_for(Agent::Claude, &[]);
+        let matches = filter(&commands, "cmp");
+        assert_eq!(commands[matches[0]].name, "/compact");
+        assert_eq!(filter(&commands, "").len(), commands.len());
+        assert!(filter(&commands, "zzzz").is_empty());
+    }
+
+    #[test]
+    fn argument_prefix_strips_placeholder() {
+        let model = custom("/model <name>", None);
+        assert_eq!(model.argument_prefix(), Some("/model "));
+        assert_eq!(custom("/cost", None).argument_prefix(), None);
+    }
+}
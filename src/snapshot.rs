@@ -0,0 +1,325 @@
+use crate::cache::load_cached_sessions;
+use crate::config::AppConfig;
+use crate::history::resume_command;
+use crate::launch::quote;
+use crate::session::{now_millis, Agent, AgentSession};
+use crate::tmux::TmuxClient;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// The agent fleet at a point in time, written by `agent-dash snapshot save`
+/// and replayed by `agent-dash snapshot restore`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub saved_at: u64,
+    pub windows: Vec<SnapshotWindow>,
+}
+
+/// One agent pane and the window it lived in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotWindow {
+    #[serde(rename = "sessionName")]
+    pub tmux_session_name: String,
+    pub window_index: u32,
+    pub window_name: String,
+    pub cwd: Option<String>,
+    pub agent: Agent,
+    pub command: String,
+    pub session_id: Option<String>,
+}
+
+impl SnapshotWindow {
+    /// Command that relaunches the agent, resuming its conversation when the
+    /// session id is known.
+    pub fn launch_command(&self) -> String {
+        match &self.session_id {
+            Some(id) => resume_command(&self.command, self.agent, id),
+            None => self.command.clone(),
+        }
+    }
+
+    fn label(&self) -> String {
+        format!("{}:{}", self.tmux_session_name, self.window_name)
+    }
+}
+
+pub fn default_path() -> PathBuf {
+    dirs::home_dir()
+        .expect("home directory not found")
+        .join(".config/agent-dash/snapshot.json")
+}
+
+/// Window index from a `session:window.pane` target.
+fn window_index(pane_target: &str) -> Option<u32> {
+    let (_, rest) = pane_target.rsplit_once(':')?;
+    let index = rest.split('.').next()?;
+    index.parse().ok()
+}
+
+/// Shell command that starts `agent` again with the arguments of `args`, a
+/// process command line from `ps`. Resume and continue flags are dropped since
+/// the snapshot adds its own. `ps` joins argv with spaces, so each word is
+/// quoted on its own.
+fn relaunch_command(args: &str, agent: Agent) -> String {
+    let (resume_long, resume_short) = match agent {
+        Agent::Claude => ("--resume", "-r"),
+        Agent::Opencode => ("--session", "-s"),
+    };
+    let mut words = Vec::new();
+    let mut tokens = args.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        if token == resume_long || token == resume_short {
+            tokens.next_if(|next| !next.starts_with('-'));
+            continue;
+        }
+        if token == "--continue" || token == "-c" || token.starts_with(&format!("{}=", resume_long))
+        {
+            continue;
+        }
+        words.push(quote(token));
+    }
+    words.join(" ")
+}
+
+/// Builds snapshot windows from live sessions, ordered by tmux session and
+/// window index. Panes whose window name is unknown fall back to the agent
+/// binary name.
+pub fn snapshot_windows(sessions: &[AgentSession], config: &AppConfig) -> Vec<SnapshotWindow> {
+    let mut windows: Vec<SnapshotWindow> = sessions
+        .iter()
+        .filter_map(|session| {
+            let window_index = window_index(&session.pane_target)?;
+            let window_name = session
+                .window_name
+                .clone()
+                .unwrap_or_else(|| session.agent.binary().to_string());
+            Some(SnapshotWindow {
+                tmux_session_name: session.tmux_session_name.clone(),
+                window_index,
+                window_name,
+                cwd: session.cwd.clone(),
+                agent: session.agent,
+                command: session
+                    .command
+                    .as_deref()
+                    .map(|args| relaunch_command(args, session.agent))
+                    .filter(|command| !command.is_empty())
+                    .unwrap_or_else(|| config.command_for(session.agent)),
+                session_id: session.session_id.clone(),
+            })
+        })
+        .collect();
+    windows.sort_by(|a, b| {
+        a.tmux_session_name
+            .cmp(&b.tmux_session_name)
+            .then(a.window_index.cmp(&b.window_index))
+    });
+    windows
+}
+
+/// Records every live agent pane to `path`. When no tmux server is reachable
+/// the dashboard's last cached session list is used instead, so a snapshot
+/// can still be taken right after the server went away.
+pub async fn save(config: &AppConfig, path: &Path) -> Result<()> {
+    let tmux = TmuxClient::new(config);
+    let mut sessions = tmux.discover_sessions().await?.sessions;
+    if sessions.is_empty() {
+        sessions = load_cached_sessions()
+            .map(|cached| cached.sessions)
+            .unwrap_or_default();
+    } else {
+        crate::enrichment::merge_into(&mut sessions);
+    }
+
+    let snapshot = Snapshot {
+        saved_at: now_millis(),
+        windows: snapshot_windows(&sessions, config),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&snapshot)?)
+        .with_context(|| format!("writing {}", path.display()))?;
+
+    let resumable = snapshot
+        .windows
+        .iter()
+        .filter(|w| w.session_id.is_some())
+        .count();
+    println!(
+        "Saved {} agent window(s) ({} resumable) to {}",
+        snapshot.windows.len(),
+        resumable,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Recreates the tmux sessions and windows recorded in `path` and relaunches
+/// each agent, in the saved window order. Agents that shared a window are
+/// split into one window again. Agents whose session id is already running
+/// are skipped.
+pub async fn restore(config: &AppConfig, path: &Path) -> Result<()> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let snapshot: Snapshot =
+        serde_json::from_str(&content).with_context(|| format!("parsing {}", path.display()))?;
+
+    let tmux = TmuxClient::new(config);
+    let mut running = tmux.discover_sessions().await?.sessions;
+    crate::enrichment::merge_into(&mut running);
+    let running_ids: HashSet<String> = running.into_iter().filter_map(|s| s.session_id).collect();
+
+    let mut restored = 0;
+    let mut failed = 0;
+    // Saved (session, window index) -> a pane restored into that window, so
+    // the window's other agent panes are split into it
+    let mut restored_panes: HashMap<(&str, u32), String> = HashMap::new();
+    for window in &snapshot.windows {
+        if window
+            .session_id
+            .as_ref()
+            .is_some_and(|id| running_ids.contains(id))
+        {
+            println!("skipped {} (already running)", window.label());
+            continue;
+        }
+        let key = (window.tmux_session_name.as_str(), window.window_index);
+        let split_target = restored_panes.get(&key).map(String::as_str);
+        match restore_window(&tmux, window, split_target).await {
+            Ok(pane_id) => {
+                restored_panes.entry(key).or_insert(pane_id);
+                restored += 1;
+                match &window.session_id {
+                    Some(id) => println!("restored {} (resumed {})", window.label(), id),
+                    None => println!("restored {} (new session)", window.label()),
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("failed {}: {}", window.label(), e);
+            }
+        }
+    }
+
+    println!(
+        "Restored {} of {} window(s)",
+        restored,
+        snapshot.windows.len()
+    );
+    if failed > 0 {
+        return Err(anyhow!("{} window(s) failed to restore", failed));
+    }
+    Ok(())
+}
+
+/// Restores one agent pane and returns its pane id. Panes of a window that
+/// was already restored are split into it; otherwise a new window (or the
+/// session itself) is created.
+async fn restore_window(
+    tmux: &TmuxClient<'_>,
+    window: &SnapshotWindow,
+    split_target: Option<&str>,
+) -> Result<String> {
+    let command = window.launch_command();
+    let cwd = window.cwd.as_deref();
+    if let Some(target) = split_target {
+        return tmux.split_window(target, cwd, &command).await;
+    }
+    if !tmux.has_session(&window.tmux_session_name).await {
+        return tmux
+            .create_session(
+                &window.tmux_session_name,
+                &window.window_name,
+                cwd,
+                &command,
+            )
+            .await;
+    }
+    let created = tmux
+        .create_window_with_command(&window.tmux_session_name, cwd, &command)
+        .await?
+        .ok_or_else(|| anyhow!("tmux did not report the new window"))?;
+    tmux.rename_window(&created.pane_id, &window.window_name)
+        .await?;
+    Ok(created.pane_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(pane_target: &str, session_id: Option<&str>) -> AgentSession {
+        AgentSession {
+            pane_target: pane_target.to_string(),
+            tmux_session_name: pane_target.split(':').next().unwrap().to_string(),
+            session_id: session_id.map(str::to_string),
+            cwd: Some("/src/api".to_string()),
+            ..AgentSession::test("%1")
+        }
+    }
+
+    #[test]
+    fn window_index_from_target() {
+        assert_eq!(window_index("main:3.1"), Some(3));
+        assert_eq!(window_index("my:session:12.0"), Some(12));
+        assert_eq!(window_index("broken"), None);
+    }
+
+    #[test]
+    fn snapshot_windows_sorted_with_names() {
+        let config = AppConfig {
+            command: "claude".to_string(),
+            ..AppConfig::default()
+        };
+        let mut sessions = vec![
+            session("work:2.0", None),
+            session("work:1.0", Some("abc")),
+            session("alpha:0.0", None),
+        ];
+        sessions[1].window_name = Some("api".to_string());
+        sessions[1].command = Some("claude --model opus".to_string());
+        let windows = snapshot_windows(&sessions, &config);
+        let labels: Vec<String> = windows.iter().map(|w| w.label()).collect();
+        assert_eq!(labels, ["alpha:claude", "work:api", "work:claude"]);
+        assert_eq!(windows[1].session_id.as_deref(), Some("abc"));
+        assert_eq!(windows[0].command, "claude");
+        assert_eq!(windows[1].command, "claude --model opus");
+    }
+
+    #[test]
+    fn relaunch_keeps_flags_and_drops_resume() {
+        assert_eq!(
+            relaunch_command("claude --model opus --resume abc -c", Agent::Claude),
+            "claude --model opus"
+        );
+        assert_eq!(
+            relaunch_command("opencode --session=ses_1 --port 4096", Agent::Opencode),
+            "opencode --port 4096"
+        );
+        assert_eq!(
+            relaunch_command("claude it's", Agent::Claude),
+            "claude 'it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn launch_command_resumes_known_sessions() {
+        let mut window = SnapshotWindow {
+            tmux_session_name: "work".to_string(),
+            window_index: 1,
+            window_name: "api".to_string(),
+            cwd: None,
+            agent: Agent::Claude,
+            command: "claude".to_string(),
+            session_id: None,
+        };
+        assert_eq!(window.launch_command(), "claude");
+        window.session_id = Some("abc".to_string());
+        assert_eq!(window.launch_command(), "claude --resume abc");
+    }
+}
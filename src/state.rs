@@ -1,4 +1,6 @@
-use crate::session::SessionStatus;
+use crate::grouping::GroupBy;
+use crate::session::{AgentSession, ManualOrder, SessionStatus, SortMode};
+use crate::views::SavedView;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
@@ -11,6 +13,22 @@ struct InstanceState {
     collapsed_hidden_groups: Vec<String>,
     hidden_section_collapsed: Option<bool>,
     group_hidden_collapsed: Vec<String>,
+    recent_section_collapsed: Option<bool>,
+    #[serde(deserialize_with = "lenient")]
+    sort_mode: Option<SortMode>,
+    #[serde(deserialize_with = "lenient")]
+    group_by: Option<GroupBy>,
+}
+
+/// Reads an enum setting, dropping values this version does not know so an
+/// older binary does not discard the whole state file.
+pub fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -19,10 +37,28 @@ struct InstanceState {
 struct PersistedState {
     unread_pane_ids: Vec<String>,
     prev_status_map: HashMap<String, SessionStatus>,
+    status_changed_at: HashMap<String, u64>,
     unread_order: HashMap<String, u64>,
     unread_counter: u64,
     hidden_pane_ids: Vec<String>,
     hidden_groups: Vec<String>,
+    exited_sessions: Vec<AgentSession>,
+    prompt_queues: HashMap<String, Vec<String>>,
+    saved_views: Vec<SavedView>,
+    /// Pinned sessions by session id, or pane id when no id is known.
+    pinned_sessions: Vec<String>,
+    pinned_groups: Vec<String>,
+    /// Tags and notes by session id, or pane id when no id is known.
+    session_tags: HashMap<String, Vec<String>>,
+    session_notes: HashMap<String, String>,
+    /// Session names keyed like tags, and group names keyed by group key.
+    session_names: HashMap<String, String>,
+    group_aliases: HashMap<String, String>,
+    /// Worktree created for a pane with `W`, by pane id.
+    session_worktrees: HashMap<String, String>,
+    /// Launch profile last started with `c`, by group key.
+    last_launch_profiles: HashMap<String, String>,
+    manual_order: ManualOrder,
     per_instance: HashMap<String, InstanceState>,
 }
 
@@ -49,14 +85,30 @@ fn resolve_instance_id(shared_state: bool) -> String {
 pub struct LoadedState {
     pub unread_pane_ids: HashSet<String>,
     pub prev_status_map: HashMap<String, SessionStatus>,
+    pub status_changed_at: HashMap<String, u64>,
     pub unread_order: HashMap<String, u64>,
     pub unread_counter: u64,
     pub hidden_pane_ids: HashSet<String>,
     pub hidden_groups: HashSet<String>,
+    pub exited_sessions: Vec<AgentSession>,
+    pub prompt_queues: HashMap<String, Vec<String>>,
+    pub saved_views: Vec<SavedView>,
+    pub pinned_sessions: HashSet<String>,
+    pub pinned_groups: HashSet<String>,
+    pub session_tags: HashMap<String, Vec<String>>,
+    pub session_notes: HashMap<String, String>,
+    pub session_names: HashMap<String, String>,
+    pub group_aliases: HashMap<String, String>,
+    pub session_worktrees: HashMap<String, String>,
+    pub last_launch_profiles: HashMap<String, String>,
+    pub manual_order: ManualOrder,
     pub collapsed_groups: HashSet<String>,
     pub collapsed_hidden_groups: HashSet<String>,
     pub hidden_section_collapsed: bool,
     pub group_hidden_collapsed: HashSet<String>,
+    pub recent_section_collapsed: bool,
+    pub sort_mode: SortMode,
+    pub group_by: Option<GroupBy>,
 }
 
 pub fn load_state(shared_state: bool) -> LoadedState {
@@ -74,10 +126,23 @@ pub fn load_state(shared_state: bool) -> LoadedState {
             LoadedState {
                 unread_pane_ids: parsed.unread_pane_ids.into_iter().collect(),
                 prev_status_map: parsed.prev_status_map,
+                status_changed_at: parsed.status_changed_at,
                 unread_order: parsed.unread_order,
                 unread_counter: parsed.unread_counter,
                 hidden_pane_ids: parsed.hidden_pane_ids.into_iter().collect(),
                 hidden_groups: parsed.hidden_groups.into_iter().collect(),
+                exited_sessions: parsed.exited_sessions,
+                prompt_queues: parsed.prompt_queues,
+                saved_views: parsed.saved_views,
+                pinned_sessions: parsed.pinned_sessions.into_iter().collect(),
+                pinned_groups: parsed.pinned_groups.into_iter().collect(),
+                session_tags: parsed.session_tags,
+                session_notes: parsed.session_notes,
+                session_names: parsed.session_names,
+                group_aliases: parsed.group_aliases,
+                session_worktrees: parsed.session_worktrees,
+                last_launch_profiles: parsed.last_launch_profiles,
+                manual_order: parsed.manual_order,
                 collapsed_groups: instance
                     .map(|i| i.collapsed_groups.iter().cloned().collect())
                     .unwrap_or_default(),
@@ -90,6 +155,11 @@ pub fn load_state(shared_state: bool) -> LoadedState {
                 group_hidden_collapsed: instance
                     .map(|i| i.group_hidden_collapsed.iter().cloned().collect())
                     .unwrap_or_default(),
+                recent_section_collapsed: instance
+                    .and_then(|i| i.recent_section_collapsed)
+                    .unwrap_or(true),
+                sort_mode: instance.and_then(|i| i.sort_mode).unwrap_or_default(),
+                group_by: instance.and_then(|i| i.group_by),
             }
         }
         Err(_) => empty_loaded_state(),
@@ -100,24 +170,53 @@ fn empty_loaded_state() -> LoadedState {
     LoadedState {
         unread_pane_ids: HashSet::new(),
         prev_status_map: HashMap::new(),
+        status_changed_at: HashMap::new(),
         unread_order: HashMap::new(),
         unread_counter: 0,
         hidden_pane_ids: HashSet::new(),
         hidden_groups: HashSet::new(),
+        exited_sessions: Vec::new(),
+        prompt_queues: HashMap::new(),
+        saved_views: Vec::new(),
+        pinned_sessions: HashSet::new(),
+        pinned_groups: HashSet::new(),
+        session_tags: HashMap::new(),
+        session_notes: HashMap::new(),
+        session_names: HashMap::new(),
+        group_aliases: HashMap::new(),
+        session_worktrees: HashMap::new(),
+        last_launch_profiles: HashMap::new(),
+        manual_order: ManualOrder::default(),
         collapsed_groups: HashSet::new(),
         collapsed_hidden_groups: HashSet::new(),
         hidden_section_collapsed: true,
         group_hidden_collapsed: HashSet::new(),
+        recent_section_collapsed: true,
+        sort_mode: SortMode::default(),
+        group_by: None,
     }
 }
 
 pub struct SaveArgs<'a> {
     pub unread_pane_ids: &'a HashSet<String>,
     pub prev_status_map: &'a HashMap<String, SessionStatus>,
+    pub status_changed_at: &'a HashMap<String, u64>,
     pub unread_order: &'a HashMap<String, u64>,
     pub unread_counter: u64,
     pub hidden_pane_ids: &'a HashSet<String>,
     pub hidden_groups: &'a HashSet<String>,
+    pub exited_sessions: &'a [AgentSession],
+    pub prompt_queues: &'a HashMap<String, Vec<String>>,
+    pub saved_views: &'a [SavedView],
+    pub pinned_sessions: &'a HashSet<String>,
+    pub pinned_groups: &'a HashSet<String>,
+    pub session_tags: &'a HashMap<String, Vec<String>>,
+    pub session_notes: &'a HashMap<String, String>,
+    pub session_names: &'a HashMap<String, String>,
+    pub group_aliases: &'a HashMap<String, String>,
+    pub session_worktrees: &'a HashMap<String, String>,
+    pub last_launch_profiles: &'a HashMap<String, String>,
+    pub manual_order: &'a ManualOrder,
     pub instance: Option<InstanceSaveArgs<'a>>,
     pub shared_state: bool,
 }
@@ -127,6 +226,9 @@ pub struct InstanceSaveArgs<'a> {
     pub collapsed_hidden_groups: &'a HashSet<String>,
     pub hidden_section_collapsed: bool,
     pub group_hidden_collapsed: &'a HashSet<String>,
+    pub recent_section_collapsed: bool,
+    pub sort_mode: SortMode,
+    pub group_by: GroupBy,
 }
 
 pub fn save_state(args: SaveArgs) {
@@ -138,10 +240,23 @@ pub fn save_state(args: SaveArgs) {
 
     persisted.unread_pane_ids = args.unread_pane_ids.iter().cloned().collect();
     persisted.prev_status_map = args.prev_status_map.clone();
+    persisted.status_changed_at = args.status_changed_at.clone();
     persisted.unread_order = args.unread_order.clone();
     persisted.unread_counter = args.unread_counter;
     persisted.hidden_pane_ids = args.hidden_pane_ids.iter().cloned().collect();
     persisted.hidden_groups = args.hidden_groups.iter().cloned().collect();
+    persisted.exited_sessions = args.exited_sessions.to_vec();
+    persisted.prompt_queues = args.prompt_queues.clone();
+    persisted.saved_views = args.saved_views.to_vec();
+    persisted.pinned_sessions = args.pinned_sessions.iter().cloned().collect();
+    persisted.pinned_groups = args.pinned_groups.iter().cloned().collect();
+    persisted.session_tags = args.session_tags.clone();
+    persisted.session_notes = args.session_notes.clone();
+    persisted.session_names = args.session_names.clone();
+    persisted.group_aliases = args.group_aliases.clone();
+    persisted.session_worktrees = args.session_worktrees.clone();
+    persisted.last_launch_profiles = args.last_launch_profiles.clone();
+    persisted.manual_order = args.manual_order.clone();
 
     if let Some(inst_args) = args.instance {
         let instance_id = resolve_instance_id(args.shared_state);
@@ -152,6 +267,9 @@ pub fn save_state(args: SaveArgs) {
         instance.hidden_section_collapsed = Some(inst_args.hidden_section_collapsed);
         instance.group_hidden_collapsed =
             inst_args.group_hidden_collapsed.iter().cloned().collect();
+        instance.recent_section_collapsed = Some(inst_args.recent_section_collapsed);
+        instance.sort_mode = Some(inst_args.sort_mode);
+        instance.group_by = Some(inst_args.group_by);
     }
 
     let dir = state_dir();
@@ -0,0 +1,293 @@
+use crate::session::AgentSession;
+use regex::Regex;
+use std::path::Path;
+
+/// A display-name template such as `{repo}:{branch|truncate:20}`.
+///
+/// `{field}` is replaced with a value of the tmux session;
+/// `|`-separated filters after the field transform it in order. Filter
+/// arguments follow a `:` and may be double-quoted to contain `:`, `|` or
+/// `}`. `{{` and `}}` are literal braces.
+#[derive(Debug)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Literal(String),
+    Field { field: Field, filters: Vec<Filter> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Session,
+    Window,
+    Dir,
+    Repo,
+    Branch,
+    Title,
+    Model,
+    Role,
+    Agent,
+}
+
+#[derive(Debug)]
+enum Filter {
+    Lower,
+    Upper,
+    Truncate(usize),
+    Replace(Regex, String),
+    /// Used when the value is empty.
+    Default(String),
+}
+
+/// Values a template is rendered from; missing ones render as empty.
+#[derive(Debug, Default)]
+pub struct TemplateValues {
+    pub session: String,
+    pub window: Option<String>,
+    pub dir: Option<String>,
+    pub repo: Option<String>,
+    pub branch: Option<String>,
+    pub title: Option<String>,
+    pub model: Option<String>,
+    pub role: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl TemplateValues {
+    /// Values for the tmux session `session` belongs to, with the git
+    /// repository and branch looked up from its cwd.
+    pub fn from_session(session: &AgentSession) -> Self {
+        let cwd = session.cwd.as_deref().map(Path::new);
+        let repo_root = cwd.and_then(crate::git::repo_root);
+        TemplateValues {
+            session: session.tmux_session_name.clone(),
+            window: session.window_name.clone(),
+            dir: cwd.and_then(base_name),
+            repo: repo_root.as_deref().and_then(base_name),
+            branch: repo_root.as_deref().and_then(crate::git::current_branch),
+            title: Some(session.title.clone()).filter(|t| !t.is_empty()),
+            model: session.model.clone(),
+            role: session.agent_role.clone(),
+            agent: Some(session.agent.binary().to_string()),
+        }
+    }
+
+    fn get(&self, field: Field) -> &str {
+        let value = match field {
+            Field::Session => return &self.session,
+            Field::Window => &self.window,
+            Field::Dir => &self.dir,
+            Field::Repo => &self.repo,
+            Field::Branch => &self.branch,
+            Field::Title => &self.title,
+            Field::Model => &self.model,
+            Field::Role => &self.role,
+            Field::Agent => &self.agent,
+        };
+        value.as_deref().unwrap_or("")
+    }
+}
+
+fn base_name(path: &Path) -> Option<String> {
+    path.file_name().map(|n| n.to_string_lossy().into_owned())
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    let parts = split_expression(&mut chars)?;
+                    segments.push(parse_expression(parts)?);
+                }
+                '}' => return Err("unmatched `}`".to_string()),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Template { segments })
+    }
+
+    pub fn render(&self, values: &TemplateValues) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field { field, filters } => {
+                    let value = filters
+                        .iter()
+                        .fold(values.get(*field).to_string(), |v, f| f.apply(v));
+                    out.push_str(&value);
+                }
+            }
+        }
+        out.trim().to_string()
+    }
+}
+
+impl Filter {
+    fn apply(&self, value: String) -> String {
+        match self {
+            Filter::Lower => value.to_lowercase(),
+            Filter::Upper => value.to_uppercase(),
+            Filter::Truncate(max) => value.chars().take(*max).collect(),
+            Filter::Replace(pattern, replacement) => pattern
+                .replace_all(&value, replacement.as_str())
+                .into_owned(),
+            Filter::Default(fallback) if value.is_empty() => fallback.clone(),
+            Filter::Default(_) => value,
+        }
+    }
+}
+
+/// Reads the rest of a `{...}` expression, returning its filters (the field
+/// first) with each split into name and arguments. Quotes are removed.
+fn split_expression(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+) -> Result<Vec<Vec<String>>, String> {
+    let mut parts: Vec<Vec<String>> = vec![vec![String::new()]];
+    let mut quoted = false;
+    loop {
+        let c = chars.next().ok_or("unclosed `{`")?;
+        let part = parts.last_mut().unwrap();
+        match c {
+            '\\' if quoted => part
+                .last_mut()
+                .unwrap()
+                .push(chars.next().ok_or("unclosed `{`")?),
+            '"' => quoted = !quoted,
+            c if quoted => part.last_mut().unwrap().push(c),
+            '}' => return Ok(parts),
+            '|' => parts.push(vec![String::new()]),
+            ':' => part.push(String::new()),
+            c if c.is_whitespace() => {}
+            c => part.last_mut().unwrap().push(c),
+        }
+    }
+}
+
+fn parse_expression(parts: Vec<Vec<String>>) -> Result<Segment, String> {
+    let mut parts = parts.into_iter();
+    let field_part = parts.next().unwrap_or_default();
+    let field = match field_part.first().map(String::as_str) {
+        Some("session") => Field::Session,
+        Some("window") => Field::Window,
+        Some("dir") => Field::Dir,
+        Some("repo") => Field::Repo,
+        Some("branch") => Field::Branch,
+        Some("title") => Field::Title,
+        Some("model") => Field::Model,
+        Some("role") => Field::Role,
+        Some("agent") => Field::Agent,
+        other => return Err(format!("unknown field `{}`", other.unwrap_or(""))),
+    };
+    let filters = parts.map(parse_filter).collect::<Result<_, _>>()?;
+    Ok(Segment::Field { field, filters })
+}
+
+fn parse_filter(part: Vec<String>) -> Result<Filter, String> {
+    let name = part.first().cloned().unwrap_or_default();
+    let args = &part[1..];
+    let arg = |i: usize| {
+        args.get(i)
+            .cloned()
+            .ok_or_else(|| format!("`{}` is missing an argument", name))
+    };
+    match name.as_str() {
+        "lower" => Ok(Filter::Lower),
+        "upper" => Ok(Filter::Upper),
+        "truncate" => arg(0)?
+            .parse()
+            .map(Filter::Truncate)
+            .map_err(|_| "`truncate` takes a number".to_string()),
+        "replace" => {
+            let pattern = Regex::new(&arg(0)?).map_err(|e| e.to_string())?;
+            Ok(Filter::Replace(
+                pattern,
+                args.get(1).cloned().unwrap_or_default(),
+            ))
+        }
+        "default" => Ok(Filter::Default(arg(0)?)),
+        _ => Err(format!("unknown filter `{}`", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues {
+        TemplateValues {
+            session: "work-API".to_string(),
+            window: Some("editor".to_string()),
+            dir: Some("src".to_string()),
+            repo: Some("api".to_string()),
+            branch: Some("feature/very-long-branch-name".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn render(source: &str) -> String {
+        Template::parse(source).unwrap().render(&values())
+    }
+
+    #[test]
+    fn substitutes_fields() {
+        assert_eq!(
+            render("{repo} [{branch}]"),
+            "api [feature/very-long-branch-name]"
+        );
+        assert_eq!(render("{window}/{dir}"), "editor/src");
+        assert_eq!(render("{{{session}}}"), "{work-API}");
+    }
+
+    #[test]
+    fn applies_filters_in_order() {
+        assert_eq!(render("{session|replace:^work-|lower}"), "api");
+        assert_eq!(
+            render("{branch | replace:\"^feature/\":\"f:\" | truncate:6}"),
+            "f:very"
+        );
+        assert_eq!(render("{session|upper}"), "WORK-API");
+        assert_eq!(
+            render("{repo}:{branch|replace:\"a|e\":_}"),
+            "api:f__tur_/v_ry-long-br_nch-n_m_"
+        );
+    }
+
+    #[test]
+    fn missing_values_render_empty_or_default() {
+        assert_eq!(render("{repo} {model}"), "api");
+        assert_eq!(render("{role|default:\"no role\"}"), "no role");
+        assert_eq!(render("{repo|default:none}"), "api");
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(Template::parse("{repo").is_err());
+        assert!(Template::parse("repo}").is_err());
+        assert!(Template::parse("{nope}").is_err());
+        assert!(Template::parse("{repo|shout}").is_err());
+        assert!(Template::parse("{repo|truncate:x}").is_err());
+        assert!(Template::parse("{repo|replace:\"(\"}").is_err());
+    }
+}
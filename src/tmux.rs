@@ -12,14 +12,13 @@ impl<'a> TmuxClient<'a> {
         Self { config }
     }
 
-    pub async fn discover_sessions(&self) -> Result<Vec<AgentSession>> {
-        let format =
-            "#{pane_id}\t#{pane_pid}\t#{pane_title}\t#{session_name}:#{window_index}.#{pane_index}";
+    pub async fn discover_sessions(&self) -> Result<DiscoveredPanes> {
+        let format = "#{pane_id}\t#{pane_pid}\t#{pane_title}\t#{session_name}:#{window_index}.#{pane_index}\t#{pane_current_path}\t#{window_name}";
         let output = run_command("tmux", &["list-panes", "-a", "-F", format]).await;
 
         let output = match output {
             Ok(o) => o,
-            Err(_) => return Ok(Vec::new()),
+            Err(_) => return Ok(DiscoveredPanes::default()),
         };
 
         struct ParsedPane {
@@ -28,6 +27,8 @@ impl<'a> TmuxClient<'a> {
             pane_title: String,
             pane_target: String,
             tmux_session_name: String,
+            cwd: Option<String>,
+            window_name: Option<String>,
         }
 
         let mut parsed = Vec::new();
@@ -47,8 +48,17 @@ impl<'a> TmuxClient<'a> {
                 pane_title: parts[2].to_string(),
                 pane_target: pane_target.to_string(),
                 tmux_session_name: tmux_session_name.to_string(),
+                cwd: parts
+                    .get(4)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| p.to_string()),
+                window_name: parts
+                    .get(5)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| p.to_string()),
             });
         }
+        let pane_ids = parsed.iter().map(|p| p.pane_id.clone()).collect();
 
         let mut set = tokio::task::JoinSet::new();
         for (i, p) in parsed.iter().enumerate() {
@@ -56,7 +66,7 @@ impl<'a> TmuxClient<'a> {
             let pane_target = p.pane_target.clone();
             set.spawn(async move {
                 let agent = detect_agent(&pid).await;
-                let content = if agent == Some(Agent::Opencode) {
+                let content = if agent.as_ref().is_some_and(|(a, _)| *a == Agent::Opencode) {
                     capture_pane_visible(&pane_target).await.ok()
                 } else {
                     None
@@ -65,17 +75,17 @@ impl<'a> TmuxClient<'a> {
             });
         }
 
-        let mut agent_map: std::collections::HashMap<usize, (Agent, Option<String>)> =
+        let mut agent_map: std::collections::HashMap<usize, (Agent, String, Option<String>)> =
             std::collections::HashMap::new();
         while let Some(result) = set.join_next().await {
-            if let Ok((i, Some(agent), content)) = result {
-                agent_map.insert(i, (agent, content));
+            if let Ok((i, Some((agent, command)), content)) = result {
+                agent_map.insert(i, (agent, command, content));
             }
         }
 
         let mut sessions = Vec::new();
         for (i, p) in parsed.into_iter().enumerate() {
-            if let Some((agent, content)) = agent_map.remove(&i) {
+            if let Some((agent, command, content)) = agent_map.remove(&i) {
                 let status = parse_session_status(agent, &p.pane_title, content.as_deref());
                 sessions.push(AgentSession {
                     pane_id: p.pane_id,
@@ -85,14 +95,16 @@ impl<'a> TmuxClient<'a> {
                     status,
                     agent,
                     session_id: None,
-                    cwd: None,
+                    cwd: p.cwd,
                     model: None,
                     agent_role: None,
+                    window_name: p.window_name,
+                    command: Some(command),
                 });
             }
         }
 
-        Ok(sessions)
+        Ok(DiscoveredPanes { sessions, pane_ids })
     }
 
     pub async fn capture_pane_content(
@@ -145,6 +157,16 @@ impl<'a> TmuxClient<'a> {
         &self,
         tmux_session_name: &str,
         cwd: Option<&str>,
+    ) -> Result<Option<CreatedPaneInfo>> {
+        self.create_window_with_command(tmux_session_name, cwd, &self.config.command)
+            .await
+    }
+
+    pub async fn create_window_with_command(
+        &self,
+        tmux_session_name: &str,
+        cwd: Option<&str>,
+        command: &str,
     ) -> Result<Option<CreatedPaneInfo>> {
         let format = "#{pane_id}\t#{pane_title}\t#{session_name}:#{window_index}.#{pane_index}";
         let mut args = vec![
@@ -160,7 +182,7 @@ impl<'a> TmuxClient<'a> {
             args.push("-c");
             args.push(cwd);
         }
-        args.push(&self.config.command);
+        args.push(command);
 
         let output = run_command("tmux", &args).await?;
         let parts: Vec<&str> = output.trim().split('\t').collect();
@@ -182,6 +204,71 @@ impl<'a> TmuxClient<'a> {
         }))
     }
 
+    pub async fn has_session(&self, tmux_session_name: &str) -> bool {
+        // `=` makes tmux match the name exactly instead of by prefix
+        let target = format!("={}", tmux_session_name);
+        run_command("tmux", &["has-session", "-t", &target])
+            .await
+            .is_ok()
+    }
+
+    /// Creates a detached tmux session whose first window runs `command`,
+    /// returning the id of the window's pane.
+    pub async fn create_session(
+        &self,
+        tmux_session_name: &str,
+        window_name: &str,
+        cwd: Option<&str>,
+        command: &str,
+    ) -> Result<String> {
+        let mut args = vec![
+            "new-session",
+            "-d",
+            "-P",
+            "-F",
+            "#{pane_id}",
+            "-s",
+            tmux_session_name,
+            "-n",
+            window_name,
+        ];
+        if let Some(cwd) = cwd {
+            args.push("-c");
+            args.push(cwd);
+        }
+        args.push(command);
+        let output = run_command("tmux", &args).await?;
+        Ok(output.trim().to_string())
+    }
+
+    /// Splits the window containing pane `target`, running `command` in the
+    /// new pane, and returns the new pane's id.
+    pub async fn split_window(
+        &self,
+        target: &str,
+        cwd: Option<&str>,
+        command: &str,
+    ) -> Result<String> {
+        let mut args = vec!["split-window", "-d", "-P", "-F", "#{pane_id}", "-t", target];
+        if let Some(cwd) = cwd {
+            args.push("-c");
+            args.push(cwd);
+        }
+        args.push(command);
+        let output = run_command("tmux", &args).await?;
+        Ok(output.trim().to_string())
+    }
+
+    pub async fn rename_session(&self, from: &str, to: &str) -> Result<()> {
+        run_command("tmux", &["rename-session", "-t", from, to]).await?;
+        Ok(())
+    }
+
+    pub async fn rename_window(&self, target: &str, window_name: &str) -> Result<()> {
+        run_command("tmux", &["rename-window", "-t", target, window_name]).await?;
+        Ok(())
+    }
+
     pub async fn get_pane_cwd(&self, target: &str) -> Result<String> {
         let output = run_command(
             "tmux",
@@ -197,6 +284,24 @@ impl<'a> TmuxClient<'a> {
         Ok(output.trim().to_string())
     }
 
+    /// Restarts the agent in a pane whose agent process has exited, killing
+    /// whatever is left running there (typically the login shell).
+    pub async fn respawn_pane(
+        &self,
+        pane_target: &str,
+        cwd: Option<&str>,
+        command: &str,
+    ) -> Result<()> {
+        let mut args = vec!["respawn-pane", "-k", "-t", pane_target];
+        if let Some(cwd) = cwd {
+            args.push("-c");
+            args.push(cwd);
+        }
+        args.push(command);
+        run_command("tmux", &args).await?;
+        Ok(())
+    }
+
     pub async fn kill_pane(&self, pane_target: &str) -> Result<()> {
         run_command("tmux", &["kill-pane", "-t", pane_target]).await?;
         Ok(())
@@ -304,6 +409,35 @@ pub async fn capture_pane_visible(pane_target: &str) -> Result<String> {
     run_command("tmux", &["capture-pane", "-p", "-t", pane_target]).await
 }
 
+/// Types `text` into the pane as a bracketed paste and submits it with Enter.
+pub async fn send_prompt(pane_target: &str, text: &str) -> Result<()> {
+    // One buffer per pane so concurrent sends don't clobber each other
+    let buffer = format!("agent-dash-prompt-{}", pane_target);
+    run_command("tmux", &["set-buffer", "-b", &buffer, "--", text]).await?;
+    run_command(
+        "tmux",
+        &["paste-buffer", "-p", "-d", "-b", &buffer, "-t", pane_target],
+    )
+    .await?;
+    // Give the agent a moment to finish handling the paste before submitting
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    run_command("tmux", &["send-keys", "-t", pane_target, "Enter"]).await?;
+    Ok(())
+}
+
+/// Sends the key that makes `agent` stop its current turn. opencode only
+/// interrupts on a second Escape.
+pub async fn send_interrupt(pane_target: &str, agent: Agent) -> Result<()> {
+    let presses = match agent {
+        Agent::Claude => 1,
+        Agent::Opencode => 2,
+    };
+    for _ in 0..presses {
+        run_command("tmux", &["send-keys", "-t", pane_target, "Escape"]).await?;
+    }
+    Ok(())
+}
+
 pub async fn send_scroll_up(pane_target: &str, col: u16, row: u16) -> Result<()> {
     let seq = format!("\x1b[<64;{col};{row}M");
     run_command("tmux", &["send-keys", "-l", "-t", pane_target, &seq]).await?;
@@ -316,6 +450,14 @@ pub async fn send_scroll_down(pane_target: &str, col: u16, row: u16) -> Result<(
     Ok(())
 }
 
+/// Result of a discovery pass: the agent sessions found, plus the ids of every
+/// live pane so callers can tell an exited agent from a closed pane.
+#[derive(Default)]
+pub struct DiscoveredPanes {
+    pub sessions: Vec<AgentSession>,
+    pub pane_ids: std::collections::HashSet<String>,
+}
+
 pub struct CreatedPaneInfo {
     pub pane_id: String,
     pub pane_title: String,
@@ -338,10 +480,11 @@ async fn run_command(cmd: &str, args: &[&str]) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-async fn detect_agent(parent_pid: &str) -> Option<Agent> {
+/// The agent running in or under `parent_pid`, with its command line.
+async fn detect_agent(parent_pid: &str) -> Option<(Agent, String)> {
     if let Ok(output) = run_command("ps", &["-o", "comm=,args=", "-p", parent_pid]).await {
         if let Some(agent) = parse_process_agent(&output) {
-            return Some(agent);
+            return Some((agent, process_args(&output)));
         }
     }
 
@@ -353,18 +496,27 @@ async fn detect_agent(parent_pid: &str) -> Option<Agent> {
     for child_pid in children.lines().filter(|l| !l.is_empty()) {
         if let Ok(output) = run_command("ps", &["-o", "comm=,args=", "-p", child_pid]).await {
             if let Some(agent) = parse_process_agent(&output) {
-                return Some(agent);
+                return Some((agent, process_args(&output)));
             }
         }
         // Recursive check via Box::pin for async recursion
-        if let Some(agent) = Box::pin(detect_agent(child_pid)).await {
-            return Some(agent);
+        if let Some(found) = Box::pin(detect_agent(child_pid)).await {
+            return Some(found);
         }
     }
 
     None
 }
 
+/// The args column of `ps -o comm=,args=` output.
+fn process_args(ps_output: &str) -> String {
+    ps_output
+        .trim()
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, args)| args.trim_start())
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -411,6 +563,15 @@ mod tests {
         assert_eq!(parse_process_agent("/bin/zsh /bin/zsh"), None);
     }
 
+    #[test]
+    fn process_args_drops_comm() {
+        assert_eq!(
+            process_args("claude claude --model opus\n"),
+            "claude --model opus"
+        );
+        assert_eq!(process_args("claude"), "");
+    }
+
     #[test]
     fn test_parse_process_agent_empty() {
         assert_eq!(parse_process_agent(""), None);
@@ -1,15 +1,64 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
-use crate::app::AppState;
+use crate::app::{AppState, PendingConfirm};
 
 const PRIMARY: Color = Color::Rgb(0xD9, 0x77, 0x57);
+const MAX_LISTED: usize = 10;
 
 pub fn render(frame: &mut Frame, state: &AppState) {
-    let area = frame.area();
+    let Some(ref pending) = state.pending_confirm else {
+        return;
+    };
+    let text_color = Color::Rgb(0xCC, 0xCC, 0xCC);
+    let dim_color = Color::Rgb(0x66, 0x66, 0x66);
+
+    let mut lines = Vec::new();
+    let mut hint = "[Enter] Confirm  [Esc] Cancel".to_string();
+    match pending {
+        PendingConfirm::KillPane(target) => {
+            lines.push(Line::from(format!("Close session {}?", target)).fg(text_color));
+        }
+        PendingConfirm::Interrupt { label, .. } => {
+            lines.push(Line::from(format!("Interrupt {}?", label)).fg(text_color));
+        }
+        PendingConfirm::RemoveWorktrees(paths) => {
+            let question = match paths.as_slice() {
+                [path] => format!("Remove worktree {}?", path.display()),
+                paths => format!("Remove {} worktrees?", paths.len()),
+            };
+            lines.push(Line::from(question).fg(text_color));
+            if paths.len() > 1 {
+                for path in paths.iter().take(MAX_LISTED) {
+                    lines.push(Line::from(path.display().to_string()).fg(dim_color));
+                }
+            }
+            lines.push(Line::from("No uncommitted changes; the branch is kept.").fg(dim_color));
+        }
+        PendingConfirm::KillPanes { targets, typed } => {
+            lines.push(Line::from(format!("Close {} sessions?", targets.len())).fg(text_color));
+            for (target, label) in targets.iter().take(MAX_LISTED) {
+                lines.push(Line::from(format!("{}  {}", target, label)).fg(dim_color));
+            }
+            if targets.len() > MAX_LISTED {
+                lines.push(
+                    Line::from(format!("… and {} more", targets.len() - MAX_LISTED)).fg(dim_color),
+                );
+            }
+            if targets.len() >= state.config.bulk_kill_confirm_threshold {
+                lines.push(Line::from(vec![
+                    Span::styled("Type kill to confirm: ", Style::default().fg(text_color)),
+                    Span::styled(typed.as_str(), Style::default().fg(PRIMARY)),
+                ]));
+                hint = "[Enter] Confirm once typed  [Esc] Cancel".to_string();
+            }
+        }
+    }
+    lines.push(Line::from(hint).fg(dim_color));
 
-    let width = area.width * 40 / 100;
-    let height = 5;
+    let area = frame.area();
+    let width = (area.width * 40 / 100).max(40).min(area.width);
+    let height = (lines.len() as u16 + 3).min(area.height);
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
     let popup_area = Rect::new(x, y, width, height);
@@ -27,18 +76,14 @@ pub fn render(frame: &mut Frame, state: &AppState) {
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    if let Some(ref target) = state.pending_confirm_target {
-        let message =
-            Line::from(format!("Close session {}?", target)).fg(Color::Rgb(0xCC, 0xCC, 0xCC));
-        let hint = Line::from("[Enter] Confirm  [Esc] Cancel").fg(Color::Rgb(0x66, 0x66, 0x66));
-
-        let msg_area = Rect::new(inner.x + 1, inner.y, inner.width.saturating_sub(2), 1);
-        let hint_area = Rect::new(inner.x + 1, inner.y + 1, inner.width.saturating_sub(2), 1);
-
-        frame.render_widget(
-            Paragraph::new(message).alignment(Alignment::Center),
-            msg_area,
-        );
-        frame.render_widget(Paragraph::new(hint).alignment(Alignment::Center), hint_area);
-    }
+    let text_area = Rect::new(
+        inner.x + 1,
+        inner.y,
+        inner.width.saturating_sub(2),
+        inner.height,
+    );
+    frame.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center),
+        text_area,
+    );
 }
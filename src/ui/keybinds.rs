@@ -52,7 +52,67 @@ pub const KEYBINDS: &[KeybindEntry] = &[
     },
     KeybindEntry {
         key: "c",
-        description: "Create new session",
+        description: "Create new session (pick launch profile and directory)",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "W",
+        description: "New session on a new git worktree",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "R",
+        description: "Respawn exited / resume closed session",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "d",
+        description: "Dismiss exited / closed session",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "i",
+        description: "Send a prompt to the agent",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "E",
+        description: "Compose a prompt in $EDITOR",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "Space",
+        description: "Mark session or group for broadcast",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "V",
+        description: "Start / commit visual range selection",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "A",
+        description: "Mark all sessions in group",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "B",
+        description: "Broadcast a prompt to marked sessions",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: ":",
+        description: "Slash-command palette",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "Q",
+        description: "Edit queued prompts",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "I",
+        description: "Interrupt busy agent",
         context: "sessions",
     },
     KeybindEntry {
@@ -75,11 +135,66 @@ pub const KEYBINDS: &[KeybindEntry] = &[
         description: "Toggle flat view",
         context: "sessions",
     },
+    KeybindEntry {
+        key: "s",
+        description: "Cycle sort mode",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "g",
+        description: "Cycle grouping (session, repo, cwd, agent, model, role)",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "J / K",
+        description: "Move session or group down / up (manual sort)",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "p",
+        description: "Pin / unpin session or group",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "t",
+        description: "Edit session tags",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "n",
+        description: "Edit session note",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "N",
+        description: "Rename session or group",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "2-9",
+        description: "Switch to saved view tab (1 for All)",
+        context: "sessions",
+    },
+    KeybindEntry {
+        key: "S",
+        description: "Save filter and layout as a view",
+        context: "sessions",
+    },
     KeybindEntry {
         key: "/ ?",
         description: "Search forward / backward",
         context: "preview",
     },
+    KeybindEntry {
+        key: "D",
+        description: "Toggle working-tree diff of the session's repo",
+        context: "preview",
+    },
+    KeybindEntry {
+        key: "[ / ]",
+        description: "Previous / next file in diff",
+        context: "preview",
+    },
     KeybindEntry {
         key: "?",
         description: "Toggle help",
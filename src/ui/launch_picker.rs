@@ -0,0 +1,109 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+
+use crate::app::AppState;
+use crate::grouping::shorten_home;
+
+const PRIMARY: Color = Color::Rgb(0xD9, 0x77, 0x57);
+const SELECTED_BG: Color = Color::Rgb(0x44, 0x44, 0x44);
+const MAX_ROWS: u16 = 12;
+
+pub fn render(frame: &mut Frame, state: &AppState) {
+    let Some(ref picker) = state.launch_picker else {
+        return;
+    };
+    let matches = picker.matches();
+    let area = frame.area();
+
+    let width = (area.width * 70 / 100).max(30).min(area.width);
+    let height = (matches.len() as u16).clamp(1, MAX_ROWS) + 3;
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let popup_area = Rect::new(x, y, width, height.min(area.height));
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" New session → {} ", picker.tmux_session_name))
+        .border_style(Style::default().fg(PRIMARY));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let input_area = Rect::new(inner.x + 1, inner.y, inner.width.saturating_sub(2), 1);
+    let list_area = Rect::new(
+        inner.x + 1,
+        inner.y + 1,
+        inner.width.saturating_sub(2),
+        inner.height.saturating_sub(1),
+    );
+
+    let input = Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Rgb(0x88, 0x88, 0x88))),
+        Span::styled(picker.query.as_str(), Style::default().fg(Color::White)),
+    ]);
+    frame.render_widget(Paragraph::new(input), input_area);
+    frame.set_cursor_position((input_area.x + 2 + picker.cursor as u16, input_area.y));
+
+    if matches.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Line::from("No matching profiles").fg(Color::Rgb(0x66, 0x66, 0x66))),
+            list_area,
+        );
+        return;
+    }
+
+    let dir_label = |cwd: Option<&str>| match cwd {
+        Some(cwd) => shorten_home(cwd),
+        None => "(pane directory)".to_string(),
+    };
+    let name_width = matches
+        .iter()
+        .map(|&i| {
+            picker.profiles[picker.entries[i].profile]
+                .name
+                .chars()
+                .count()
+        })
+        .max()
+        .unwrap_or(0);
+    let dir_width = matches
+        .iter()
+        .map(|&i| dir_label(picker.entries[i].cwd.as_deref()).chars().count())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = matches
+        .iter()
+        .enumerate()
+        .map(|(row, &i)| {
+            let entry = &picker.entries[i];
+            let profile = &picker.profiles[entry.profile];
+            let bg = if row == picker.selected {
+                SELECTED_BG
+            } else {
+                Color::Reset
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<width$}  ", profile.name, width = name_width),
+                    Style::default().fg(Color::White).bg(bg),
+                ),
+                Span::styled(
+                    format!(
+                        "{:<width$}  ",
+                        dir_label(entry.cwd.as_deref()),
+                        width = dir_width
+                    ),
+                    Style::default().fg(Color::Rgb(0xCC, 0xCC, 0xCC)).bg(bg),
+                ),
+                Span::styled(
+                    picker.summaries[entry.profile].as_str(),
+                    Style::default().fg(Color::Rgb(0x88, 0x88, 0x88)).bg(bg),
+                ),
+            ]))
+            .style(Style::default().bg(bg))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(picker.selected));
+    frame.render_stateful_widget(List::new(items), list_area, &mut list_state);
+}
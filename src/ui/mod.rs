@@ -4,17 +4,22 @@ use ratatui::widgets::Paragraph;
 use crate::app::{AppState, Focus};
 use crate::config::LayoutDirection;
 
+pub mod command_palette;
 pub mod confirm_dialog;
 pub mod help_overlay;
 pub mod keybinds;
+pub mod launch_picker;
 pub mod pane_preview;
+pub mod prompt_composer;
+pub mod queue_editor;
 pub mod session_list;
 
 pub fn render(frame: &mut Frame, state: &mut AppState) {
     match state.focus {
         Focus::Sessions => {
             if state.sessions_expanded {
-                session_list::render(frame, frame.area(), state, true, state.flat_view);
+                (state.session_list_rows, state.session_list_offset) =
+                    session_list::render(frame, frame.area(), state, true, state.flat_view);
                 state.preview_pane_area = Rect::default();
             } else {
                 let chunks = match state.config.layout {
@@ -27,21 +32,35 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
                             .split(frame.area())
                     }
                 };
-                session_list::render(frame, chunks[0], state, true, state.flat_view);
+                (state.session_list_rows, state.session_list_offset) =
+                    session_list::render(frame, chunks[0], state, true, state.flat_view);
                 state.preview_pane_area = chunks[1];
                 pane_preview::render(frame, chunks[1], state, false);
             }
         }
         Focus::Preview => {
+            state.session_list_rows = Rect::default();
             state.preview_pane_area = frame.area();
             pane_preview::render(frame, frame.area(), state, true);
         }
     }
 
     // Overlays rendered on top of main layout
-    if state.pending_confirm_target.is_some() {
+    if state.pending_confirm.is_some() {
         confirm_dialog::render(frame, state);
     }
+    if state.queue_editor.is_some() {
+        queue_editor::render(frame, state);
+    }
+    if state.command_palette.is_some() {
+        command_palette::render(frame, state);
+    }
+    if state.launch_picker.is_some() {
+        launch_picker::render(frame, state);
+    }
+    if state.prompt_composer.is_some() {
+        prompt_composer::render(frame, state);
+    }
     if state.show_help {
         help_overlay::render(frame, state);
     }
@@ -27,21 +27,35 @@ pub fn render(frame: &mut Frame, area: Rect, state: &mut AppState, focused: bool
         })
         .unwrap_or_default();
 
-    let title_str;
-    let title = if session_id_suffix.is_empty() {
-        if state.copy_mode.is_some() {
-            " [0] Preview [COPY] "
-        } else {
-            " [0] Preview "
+    let mut title = String::from(" [0] Preview");
+    if let Some(ref diff) = state.diff_view {
+        let row = match state.copy_mode {
+            Some(ref copy) => copy.cursor.row,
+            None => state.preview_scroll_offset,
+        };
+        let repo = diff
+            .repo_root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        title.push_str(&format!(" [DIFF {}", repo));
+        if !diff.file_starts.is_empty() {
+            title.push_str(&format!(
+                " {}/{}",
+                diff.file_at(row).max(1),
+                diff.file_starts.len()
+            ));
         }
+        title.push(']');
+    }
+    if state.copy_mode.is_some() {
+        title.push_str(" [COPY]");
+    }
+    if session_id_suffix.is_empty() {
+        title.push(' ');
     } else {
-        title_str = if state.copy_mode.is_some() {
-            format!(" [0] Preview [COPY]{}", session_id_suffix)
-        } else {
-            format!(" [0] Preview{}", session_id_suffix)
-        };
-        title_str.as_str()
-    };
+        title.push_str(&session_id_suffix);
+    }
 
     let block = Block::default()
         .borders(Borders::ALL)
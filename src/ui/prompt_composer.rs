@@ -0,0 +1,71 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::AppState;
+use crate::session::SessionStatus;
+
+const PRIMARY: Color = Color::Rgb(0xD9, 0x77, 0x57);
+
+pub fn render(frame: &mut Frame, state: &AppState) {
+    let Some(ref composer) = state.prompt_composer else {
+        return;
+    };
+    let area = frame.area();
+
+    let width = (area.width * 60 / 100).max(30).min(area.width);
+    let height = 4;
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let popup_area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(if composer.editing.is_some() {
+            format!(" Queued prompt → {} ", composer.label)
+        } else {
+            format!(" Prompt → {} ", composer.label)
+        })
+        .border_style(Style::default().fg(PRIMARY));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    // Scroll horizontally so the cursor stays visible on long prompts
+    let text_width = inner.width.saturating_sub(2) as usize;
+    let skip = composer.cursor.saturating_sub(text_width.saturating_sub(1));
+    let visible: String = composer.text.chars().skip(skip).take(text_width).collect();
+
+    let input_area = Rect::new(inner.x + 1, inner.y, inner.width.saturating_sub(2), 1);
+    let hint_area = Rect::new(inner.x + 1, inner.y + 1, inner.width.saturating_sub(2), 1);
+
+    if composer.text.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Line::from("Type a prompt...").fg(Color::Rgb(0x55, 0x55, 0x55))),
+            input_area,
+        );
+    } else {
+        frame.render_widget(
+            Paragraph::new(Line::from(visible).fg(Color::White)),
+            input_area,
+        );
+    }
+    let busy = state
+        .sessions
+        .iter()
+        .any(|s| s.pane_id == composer.pane_id && s.status == SessionStatus::Active);
+    let hint = if !composer.broadcast_pane_ids.is_empty() {
+        "[Enter] Broadcast  [Ctrl-G] Editor  [Esc] Cancel"
+    } else if composer.editing.is_some() {
+        "[Enter] Save  [Ctrl-G] Editor  [Esc] Cancel"
+    } else if busy {
+        "[Enter] Queue  [Ctrl-G] Editor  [Esc] Cancel"
+    } else {
+        "[Enter] Send  [Ctrl-G] Editor  [Esc] Cancel"
+    };
+    let hint = Line::from(hint).fg(Color::Rgb(0x66, 0x66, 0x66));
+    frame.render_widget(Paragraph::new(hint), hint_area);
+
+    frame.set_cursor_position((input_area.x + (composer.cursor - skip) as u16, input_area.y));
+}
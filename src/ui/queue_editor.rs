@@ -0,0 +1,80 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+
+use crate::app::AppState;
+
+const PRIMARY: Color = Color::Rgb(0xD9, 0x77, 0x57);
+const SELECTED_BG: Color = Color::Rgb(0x44, 0x44, 0x44);
+
+pub fn render(frame: &mut Frame, state: &AppState) {
+    let Some(ref editor) = state.queue_editor else {
+        return;
+    };
+    let queue = state
+        .prompt_queues
+        .get(&editor.pane_id)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let area = frame.area();
+
+    let width = (area.width * 60 / 100).max(30).min(area.width);
+    let height = (queue.len() as u16 + 4).clamp(5, area.height.max(5));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let popup_area = Rect::new(x, y, width, height.min(area.height));
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Queue → {} ", editor.label))
+        .border_style(Style::default().fg(PRIMARY));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let list_area = Rect::new(
+        inner.x + 1,
+        inner.y,
+        inner.width.saturating_sub(2),
+        inner.height.saturating_sub(1),
+    );
+    let hint_area = Rect::new(
+        inner.x + 1,
+        inner.y + inner.height.saturating_sub(1),
+        inner.width.saturating_sub(2),
+        1,
+    );
+
+    if queue.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Line::from("No queued prompts").fg(Color::Rgb(0x66, 0x66, 0x66))),
+            list_area,
+        );
+    } else {
+        let items: Vec<ListItem> = queue
+            .iter()
+            .enumerate()
+            .map(|(i, prompt)| {
+                // Multi-line prompts are summarized by their first line
+                let first_line = prompt.lines().next().unwrap_or_default();
+                let more = if prompt.lines().count() > 1 {
+                    " …"
+                } else {
+                    ""
+                };
+                let style = if i == editor.selected {
+                    Style::default().fg(Color::White).bg(SELECTED_BG)
+                } else {
+                    Style::default().fg(Color::Rgb(0xAA, 0xAA, 0xAA))
+                };
+                ListItem::new(format!("{}. {}{}", i + 1, first_line, more)).style(style)
+            })
+            .collect();
+        let mut list_state = ListState::default().with_selected(Some(editor.selected));
+        frame.render_stateful_widget(List::new(items), list_area, &mut list_state);
+    }
+
+    let hint = Line::from("[e] Edit  [a] Add  [d] Cancel  [J/K] Move  [Esc] Close")
+        .fg(Color::Rgb(0x66, 0x66, 0x66));
+    frame.render_widget(Paragraph::new(hint), hint_area);
+}
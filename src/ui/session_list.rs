@@ -1,29 +1,80 @@
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use std::collections::HashMap;
 
-use crate::app::AppState;
-use crate::filter_query::parse_filter_query;
-use crate::session::{Agent, PromptState, SessionStatus, VisibleItem};
+use crate::app::{session_title, visual_range, AppState};
+use crate::filter_query::{is_qualifier_token, parse_filter_query};
+use crate::git_status::GitStatus;
+use crate::grouping::GroupBy;
+use crate::session::{
+    format_elapsed, now_millis, pin_key, Agent, PromptState, SessionStatus, SortMode, VisibleItem,
+};
 
 const PRIMARY: Color = Color::Rgb(0xD9, 0x77, 0x57);
 const UNFOCUSED: Color = Color::Rgb(0x66, 0x66, 0x66);
 const UNREAD: Color = Color::Rgb(0xE5, 0xC0, 0x7B);
 const IDLE: Color = Color::Rgb(0xAA, 0xAA, 0xAA);
 const SELECTED_BG: Color = Color::Rgb(0x44, 0x44, 0x44);
+const STUCK: Color = Color::Rgb(0xE0, 0x6C, 0x75);
+const PIN_ICON: &str = "⚲ ";
+const NOTE_MAX_CHARS: usize = 20;
+/// Width tag chips may take up before the rest collapse into `+N`.
+const TAGS_MAX_WIDTH: usize = 24;
+/// Chip backgrounds; a tag always gets the same one.
+const TAG_COLORS: [Color; 6] = [
+    Color::Rgb(0x61, 0xAF, 0xEF),
+    Color::Rgb(0x98, 0xC3, 0x79),
+    Color::Rgb(0xC6, 0x78, 0xDD),
+    Color::Rgb(0x56, 0xB6, 0xC2),
+    Color::Rgb(0xE5, 0xC0, 0x7B),
+    Color::Rgb(0xD1, 0x9A, 0x66),
+];
 
-pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, flat_view: bool) {
+/// Renders the list and returns the rows its items occupy along with the
+/// index of the first visible item.
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    focused: bool,
+    flat_view: bool,
+) -> (Rect, usize) {
     let border_color = if focused { PRIMARY } else { UNFOCUSED };
     let filter_color = Color::Rgb(0x88, 0x88, 0x88);
     let flag_color = Color::Rgb(0x61, 0x96, 0xCC);
 
     let parsed = parse_filter_query(&state.session_filter_query);
+    let now = now_millis();
 
+    let mut title = if state.sort_mode == SortMode::default() {
+        " [1] Sessions ".to_string()
+    } else {
+        format!(" [1] Sessions · {} ", state.sort_mode.label())
+    };
+    if !flat_view && state.group_by != GroupBy::TmuxSession {
+        title.push_str(&format!("· by {} ", state.group_by.label()));
+    }
+    if !state.marked_pane_ids.is_empty() {
+        title.push_str(&format!("· {} marked ", state.marked_pane_ids.len()));
+    }
+    if state.visual_anchor.is_some() {
+        title.push_str("· visual ");
+    }
     let mut block = Block::default()
         .borders(Borders::ALL)
-        .title(" [1] Sessions ")
+        .title(title)
         .border_style(Style::default().fg(border_color));
 
-    if state.session_filter_active || !state.session_filter_query.is_empty() {
+    if let Some(ref input) = state.line_input {
+        let prompt = input.prompt();
+        block = block.title_bottom(Line::from(vec![
+            Span::styled(prompt, Style::default().fg(filter_color)),
+            Span::styled(input.text.as_str(), Style::default().fg(Color::White)),
+            Span::raw(" "),
+        ]));
+        let cursor_x = area.x + 1 + prompt.chars().count() as u16 + input.cursor as u16;
+        frame.set_cursor_position((cursor_x, area.y + area.height - 1));
+    } else if state.session_filter_active || !state.session_filter_query.is_empty() {
         let filter_line = if state.session_filter_query.is_empty() {
             Line::from(vec![
                 Span::styled("/", Style::default().fg(filter_color)),
@@ -39,8 +90,11 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
                 if i > 0 {
                     spans.push(Span::raw(" "));
                 }
-                let lower = token.to_lowercase();
-                if lower == "is:h" || lower == "is:hidden" {
+                if token == "OR" || token == "|" {
+                    spans.push(Span::styled(token, Style::default().fg(filter_color)));
+                } else if is_qualifier_token(token) && token.starts_with('-') {
+                    spans.push(Span::styled(token, Style::default().fg(STUCK)));
+                } else if is_qualifier_token(token) {
                     spans.push(Span::styled(token, Style::default().fg(flag_color)));
                 } else {
                     spans.push(Span::styled(token, Style::default().fg(Color::White)));
@@ -57,16 +111,29 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
         }
     }
 
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let list_area = match view_tabs_line(state) {
+        Some(tabs) => {
+            frame.render_widget(Paragraph::new(tabs), Rect { height: 1, ..inner });
+            Rect::new(
+                inner.x,
+                inner.y + 1,
+                inner.width,
+                inner.height.saturating_sub(1),
+            )
+        }
+        None => inner,
+    };
+
     if state.visible_items.is_empty() {
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
         let text = if !state.session_filter_query.is_empty() {
             Line::from(" No matching sessions").fg(UNFOCUSED)
         } else {
             Line::from(" No agent sessions found").fg(UNFOCUSED)
         };
-        frame.render_widget(text, inner);
-        return;
+        frame.render_widget(text, list_area);
+        return (list_area, 0);
     }
 
     let mut in_hidden_flags = vec![false; state.visible_items.len()];
@@ -90,6 +157,10 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
                     in_global = true;
                     in_group = false;
                 }
+                VisibleItem::RecentHeader { .. } => {
+                    in_global = false;
+                    in_group = false;
+                }
                 VisibleItem::GroupHiddenHeader { .. } => {
                     in_group = true;
                 }
@@ -98,6 +169,22 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
         }
     }
 
+    // Git status shown on each group header: that of its first session with one
+    let mut group_git_status: HashMap<&str, &GitStatus> = HashMap::new();
+    for session in &state.sessions {
+        if let Some(status) = session
+            .cwd
+            .as_deref()
+            .and_then(|cwd| state.git_status.get(cwd))
+        {
+            group_git_status
+                .entry(crate::session::group_key(&state.group_keys, session))
+                .or_insert(status);
+        }
+    }
+
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let visual_range = visual_range(state);
     let items: Vec<ListItem> = state
         .visible_items
         .iter()
@@ -163,11 +250,43 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
                     };
                     ListItem::new(Line::from(text).style(style))
                 }
+                VisibleItem::RecentHeader {
+                    count,
+                    is_collapsed,
+                } => {
+                    let arrow = if *is_collapsed { "▶" } else { "▼" };
+                    let text = format!("{} Recently closed ({})", arrow, count);
+                    ListItem::new(Line::from(text).style(dim_style(is_selected)))
+                }
+                VisibleItem::RecentSession { entry } => {
+                    let label = if entry.title.is_empty() {
+                        entry
+                            .cwd
+                            .as_deref()
+                            .and_then(|c| c.rsplit('/').next())
+                            .unwrap_or(entry.tmux_session_name.as_str())
+                    } else {
+                        entry.title.as_str()
+                    };
+                    let indent = if flat_view { " " } else { "  " };
+                    let style = if is_selected {
+                        Style::default().fg(IDLE).bg(SELECTED_BG)
+                    } else {
+                        Style::default().fg(UNFOCUSED)
+                    };
+                    let right = vec![Span::styled(
+                        format!("ended {}", format_elapsed(entry.ended_at, now)),
+                        dim_style(is_selected),
+                    )];
+                    row_with_right(format!("{}↺ {}", indent, label), style, right, inner_width)
+                }
                 VisibleItem::GroupHeader {
+                    group_key,
                     display_name,
                     session_count,
                     has_active,
                     has_unread,
+                    status_since,
                     is_collapsed,
                     in_subgroup,
                     ..
@@ -181,9 +300,14 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
                         "○"
                     };
                     let indent = if *in_subgroup { "  " } else { "" };
+                    let pin = if state.pinned_groups.contains(group_key) {
+                        PIN_ICON
+                    } else {
+                        ""
+                    };
                     let text = format!(
-                        "{}{} {} {} ({})",
-                        indent, arrow, status_icon, display_name, session_count
+                        "{}{} {} {}{} ({})",
+                        indent, arrow, status_icon, pin, display_name, session_count
                     );
                     let style = if is_selected {
                         if in_hidden_section {
@@ -196,7 +320,18 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
                     } else {
                         Style::default().fg(Color::Rgb(0xCC, 0xCC, 0xCC))
                     };
-                    ListItem::new(Line::from(text).style(style))
+                    let mut right = Vec::new();
+                    if let Some(status) = group_git_status.get(group_key.as_str()) {
+                        right.push(git_span(status, is_selected));
+                    }
+                    if let (Some(since), false) = (status_since, in_hidden_section) {
+                        let verb = if *has_active { "busy" } else { "idle" };
+                        right.push(Span::styled(
+                            format!("{} {}", verb, format_elapsed(*since, now)),
+                            dim_style(is_selected),
+                        ));
+                    }
+                    row_with_right(text, style, right, inner_width)
                 }
                 VisibleItem::Session {
                     session,
@@ -209,18 +344,22 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
                         ("○", UNFOCUSED)
                     } else {
                         match (&session.status, *is_unread) {
+                            _ if state.stuck_pane_ids.contains(&session.pane_id) => ("◌", STUCK),
                             (SessionStatus::Active, _) => ("●", PRIMARY),
+                            (SessionStatus::Exited, true) => ("✕", UNREAD),
+                            (SessionStatus::Exited, false) => ("✕", UNFOCUSED),
                             (_, true) => ("◉", UNREAD),
                             _ => ("○", IDLE),
                         }
                     };
                     // opencode title is static "OpenCode"; use tmux session name instead
-                    let label = if session.title.is_empty()
-                        || (session.agent == Agent::Opencode && session.title == "OpenCode")
+                    let title = session_title(state, session);
+                    let label = if title.is_empty()
+                        || (session.agent == Agent::Opencode && title == "OpenCode")
                     {
                         display_name.as_str()
                     } else {
-                        session.title.as_str()
+                        title
                     };
                     let base_style = if is_selected {
                         Style::default().fg(Color::White).bg(SELECTED_BG)
@@ -235,64 +374,145 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState, focused: bool, fl
                     } else {
                         "  "
                     };
-                    let left_text = format!("{}{} {}", indent, icon, label);
+                    let in_visual_range = visual_range.as_ref().is_some_and(|r| r.contains(&i));
+                    let pin = if state.pinned_sessions.contains(pin_key(session)) {
+                        PIN_ICON
+                    } else {
+                        ""
+                    };
+                    let left_text =
+                        if in_visual_range || state.marked_pane_ids.contains(&session.pane_id) {
+                            format!("{}✓{} {}{}", &indent[1..], icon, pin, label)
+                        } else {
+                            format!("{}{} {}{}", indent, icon, pin, label)
+                        };
                     let prompt_state = state
                         .prompt_states
                         .get(&session.pane_id)
                         .unwrap_or(&PromptState::None);
-                    let inner_width = area.width.saturating_sub(2) as usize;
 
-                    let effective_title_differs = !(session.title.is_empty()
-                        || session.agent == Agent::Opencode && session.title == "OpenCode");
+                    let effective_title_differs = !(title.is_empty()
+                        || session.agent == Agent::Opencode && title == "OpenCode");
                     let show_group_tag =
                         !parsed.text.is_empty() && !in_hidden_section && effective_title_differs;
 
-                    if *prompt_state == PromptState::None || in_hidden_section {
-                        if show_group_tag {
-                            let tag = display_name.as_str();
-                            let tag_width = tag.chars().count();
-                            let left_width = inner_width.saturating_sub(tag_width + 1);
-                            let left_padded = truncate_or_pad(&left_text, left_width);
-                            let tag_style = if is_selected {
-                                Style::default().fg(UNFOCUSED).bg(SELECTED_BG)
-                            } else {
-                                Style::default().fg(UNFOCUSED)
-                            };
-                            ListItem::new(Line::from(vec![
-                                Span::styled(left_padded, base_style),
-                                Span::styled(tag, tag_style),
-                            ]))
+                    let mut right = Vec::new();
+                    if show_group_tag {
+                        right.push(Span::styled(display_name.as_str(), dim_style(is_selected)));
+                    }
+                    if let Some(note) = state.session_notes.get(pin_key(session)) {
+                        let note = if note.chars().count() > NOTE_MAX_CHARS {
+                            let head: String = note.chars().take(NOTE_MAX_CHARS - 1).collect();
+                            format!("✎ {}~", head)
                         } else {
-                            ListItem::new(Line::from(left_text).style(base_style))
+                            format!("✎ {}", note)
+                        };
+                        right.push(Span::styled(note, dim_style(is_selected)));
+                    }
+                    if let Some(tags) = state.session_tags.get(pin_key(session)) {
+                        right.extend(tag_spans(tags, is_selected));
+                    }
+                    if let Some(status) = session
+                        .cwd
+                        .as_deref()
+                        .and_then(|cwd| state.git_status.get(cwd))
+                    {
+                        right.push(git_span(status, is_selected));
+                    }
+                    if !in_hidden_section {
+                        let badge = match prompt_state {
+                            PromptState::Plan => Some(("plan", Color::Rgb(0x61, 0xAF, 0xEF))),
+                            PromptState::Ask => Some(("ask", Color::Rgb(0xE5, 0xC0, 0x7B))),
+                            PromptState::None => None,
+                        };
+                        if let Some((badge_text, badge_fg)) = badge {
+                            let mut badge_style = Style::default().fg(badge_fg);
+                            if is_selected {
+                                badge_style = badge_style.bg(SELECTED_BG);
+                            }
+                            right.push(Span::styled(badge_text, badge_style));
                         }
-                    } else {
-                        let (badge_text, badge_fg) = match prompt_state {
-                            PromptState::Plan => ("plan", Color::Rgb(0x61, 0xAF, 0xEF)),
-                            PromptState::Ask => ("ask", Color::Rgb(0xE5, 0xC0, 0x7B)),
-                            PromptState::None => unreachable!(),
+                        if let Some(queue) = state.prompt_queues.get(&session.pane_id) {
+                            let mut queue_style = Style::default().fg(flag_color);
+                            if is_selected {
+                                queue_style = queue_style.bg(SELECTED_BG);
+                            }
+                            right.push(Span::styled(format!("»{}", queue.len()), queue_style));
+                        }
+                        let status_since = state.status_changed_at.get(&session.pane_id).copied();
+                        let (verb, since) = if state.stuck_pane_ids.contains(&session.pane_id) {
+                            let last_output = state.last_output_at.get(&session.pane_id).copied();
+                            ("stuck", last_output.max(status_since))
+                        } else {
+                            match session.status {
+                                SessionStatus::Active => ("busy", status_since),
+                                SessionStatus::Idle => ("idle", status_since),
+                                SessionStatus::Exited => ("exited", status_since),
+                            }
                         };
-                        let badge_width = badge_text.len();
-                        let left_width = inner_width.saturating_sub(badge_width + 1);
-                        let left_padded = truncate_or_pad(&left_text, left_width);
-
-                        let mut badge_style = Style::default().fg(badge_fg);
-                        if is_selected {
-                            badge_style = badge_style.bg(SELECTED_BG);
+                        if let Some(since) = since {
+                            right.push(Span::styled(
+                                format!("{} {}", verb, format_elapsed(since, now)),
+                                dim_style(is_selected),
+                            ));
                         }
-
-                        ListItem::new(Line::from(vec![
-                            Span::styled(left_padded, base_style),
-                            Span::styled(badge_text, badge_style),
-                        ]))
                     }
+                    row_with_right(left_text, base_style, right, inner_width)
                 }
             }
         })
         .collect();
 
-    let list = List::new(items).block(block);
+    let list = List::new(items);
     let mut list_state = ListState::default().with_selected(Some(state.selected_index));
-    frame.render_stateful_widget(list, area, &mut list_state);
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+    (list_area, list_state.offset())
+}
+
+/// Tab strip for saved views, or `None` when no views are defined. The tab
+/// whose query is the current filter is highlighted; each tab shows how many
+/// unread sessions it would list.
+fn view_tabs_line(state: &AppState) -> Option<Line<'static>> {
+    let views = state.all_views();
+    if views.is_empty() {
+        return None;
+    }
+    let active = match state.active_view {
+        Some(i)
+            if views
+                .get(i)
+                .is_some_and(|v| v.query == state.session_filter_query) =>
+        {
+            Some(i + 1)
+        }
+        _ if state.session_filter_query.is_empty() => Some(0),
+        _ => None,
+    };
+    let names = std::iter::once("All".to_string()).chain(views.into_iter().map(|v| v.name));
+    let mut spans = Vec::new();
+    for (tab, name) in names.enumerate().take(9) {
+        if tab > 0 {
+            spans.push(Span::styled(" │ ", Style::default().fg(UNFOCUSED)));
+        }
+        let label_style = if active == Some(tab) {
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(IDLE)
+        };
+        spans.push(Span::styled(
+            format!("{} ", tab + 1),
+            Style::default().fg(UNFOCUSED),
+        ));
+        spans.push(Span::styled(name, label_style));
+        let unread = state.view_unread_counts.get(tab).copied().unwrap_or(0);
+        if unread > 0 {
+            spans.push(Span::styled(
+                format!(" {}", unread),
+                Style::default().fg(UNREAD),
+            ));
+        }
+    }
+    Some(Line::from(spans))
 }
 
 fn truncate_or_pad(text: &str, width: usize) -> String {
@@ -304,3 +524,99 @@ fn truncate_or_pad(text: &str, width: usize) -> String {
         format!("{:width$}", text, width = width)
     }
 }
+
+/// Branch with dirty and ahead/behind markers; highlighted when there is
+/// uncommitted work.
+fn git_span(status: &GitStatus, is_selected: bool) -> Span<'static> {
+    if status.dirty {
+        let mut style = Style::default().fg(UNREAD);
+        if is_selected {
+            style = style.bg(SELECTED_BG);
+        }
+        Span::styled(status.summary(), style)
+    } else {
+        Span::styled(status.summary(), dim_style(is_selected))
+    }
+}
+
+/// Colored chips for `tags`, up to `TAGS_MAX_WIDTH`; tags that don't fit
+/// are counted in a trailing `+N`.
+fn tag_spans(tags: &[String], is_selected: bool) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut width = 0;
+    for (i, tag) in tags.iter().enumerate() {
+        let chip = format!(" {} ", tag);
+        let chip_width = chip.chars().count() + usize::from(i > 0);
+        if width + chip_width > TAGS_MAX_WIDTH {
+            spans.push(Span::styled(
+                format!("+{}", tags.len() - i),
+                dim_style(is_selected),
+            ));
+            break;
+        }
+        width += chip_width;
+        spans.push(Span::styled(
+            chip,
+            Style::default().fg(Color::Black).bg(tag_color(tag)),
+        ));
+    }
+    spans
+}
+
+fn tag_color(tag: &str) -> Color {
+    let hash = tag
+        .to_lowercase()
+        .bytes()
+        .fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+    TAG_COLORS[hash % TAG_COLORS.len()]
+}
+
+fn dim_style(is_selected: bool) -> Style {
+    if is_selected {
+        Style::default().fg(UNFOCUSED).bg(SELECTED_BG)
+    } else {
+        Style::default().fg(UNFOCUSED)
+    }
+}
+
+/// Lays out `left_text` padded to fill the row, followed by right-aligned
+/// spans separated by single spaces. The right side gets at most half the
+/// row; leading spans that don't fit are dropped so the status stays.
+fn row_with_right<'a>(
+    left_text: String,
+    base_style: Style,
+    mut right: Vec<Span<'a>>,
+    inner_width: usize,
+) -> ListItem<'a> {
+    let spans_width = |spans: &[Span]| {
+        spans
+            .iter()
+            .map(|s| s.content.chars().count() + 1)
+            .sum::<usize>()
+    };
+    while right.len() > 1 && spans_width(&right) > inner_width / 2 {
+        right.remove(0);
+    }
+    if let [span] = right.as_mut_slice() {
+        let max = (inner_width / 2).saturating_sub(1);
+        if span.content.chars().count() > max {
+            span.content = truncate_or_pad(&span.content, max).into();
+        }
+    }
+    if right.is_empty() {
+        return ListItem::new(Line::from(left_text).style(base_style));
+    }
+    let right_width = spans_width(&right) - 1;
+    let left_width = inner_width.saturating_sub(right_width + 1);
+    let mut spans = vec![Span::styled(
+        truncate_or_pad(&left_text, left_width),
+        base_style,
+    )];
+    for (i, span) in right.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" ", base_style));
+        }
+        spans.push(span);
+    }
+    ListItem::new(Line::from(spans))
+}
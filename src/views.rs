@@ -0,0 +1,20 @@
+use crate::session::SortMode;
+use serde::{Deserialize, Serialize};
+
+/// A named filter and layout preset shown as a tab above the session list.
+/// Views come from `views` in config.json and from ones saved in the
+/// dashboard, which live in state.json.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedView {
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    /// Flat (`true`) or grouped (`false`) list; unset keeps the current one.
+    #[serde(default)]
+    pub flat: Option<bool>,
+    /// Sort mode; unset, or one this version doesn't know, keeps the
+    /// current one.
+    #[serde(default, deserialize_with = "crate::state::lenient")]
+    pub sort: Option<SortMode>,
+}
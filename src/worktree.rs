@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const GIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the worktree for `branch` of the repository at `repo_root` goes:
+/// `<dir>/<repo>/<branch>` with a configured `dir`, otherwise a
+/// `<repo>-worktrees` directory next to the repository. Slashes in the branch
+/// name become dashes so each worktree is one directory.
+pub fn worktree_path(dir: Option<&Path>, repo_root: &Path, branch: &str) -> PathBuf {
+    let repo = repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "repo".to_string());
+    let leaf = branch.replace('/', "-");
+    match dir {
+        Some(dir) => dir.join(repo).join(leaf),
+        None => repo_root
+            .parent()
+            .unwrap_or(repo_root)
+            .join(format!("{}-worktrees", repo))
+            .join(leaf),
+    }
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    crate::git::run(dir, args, GIT_TIMEOUT).await
+}
+
+/// Checks that `branch` is a valid new branch name by git's own rules, so a
+/// bad name is reported before anything is created. Names starting with `-`
+/// are rejected up front since git would read them as options.
+pub async fn check_branch_name(repo_root: &Path, branch: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid branch name: {}", branch);
+    if branch.starts_with('-') {
+        return Err(invalid());
+    }
+    git(repo_root, &["check-ref-format", "--branch", branch])
+        .await
+        .map(|_| ())
+        .map_err(|_| invalid())
+}
+
+/// Main work tree of the repository containing `root`, so a worktree made
+/// from inside another worktree lands next to the main checkout.
+pub async fn main_root(root: &Path) -> PathBuf {
+    let common = git(
+        root,
+        &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+    )
+    .await;
+    match common {
+        Ok(dir) => Path::new(dir.trim())
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| root.to_path_buf()),
+        Err(_) => root.to_path_buf(),
+    }
+}
+
+/// Adds a worktree at `path` checking out `branch`, creating the branch from
+/// HEAD unless it already exists.
+pub async fn add(repo_root: &Path, branch: &str, path: &Path) -> Result<(), String> {
+    let path_arg = path.to_string_lossy();
+    let exists = git(
+        repo_root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{}", branch),
+        ],
+    )
+    .await
+    .is_ok();
+    let args: Vec<&str> = if exists {
+        vec!["worktree", "add", &path_arg, branch]
+    } else {
+        vec!["worktree", "add", "-b", branch, &path_arg]
+    };
+    git(repo_root, &args).await.map(|_| ())
+}
+
+/// Whether the worktree at `path` has no staged, unstaged or untracked
+/// changes. `None` when git can't tell, e.g. the directory is gone.
+pub async fn is_clean(path: &Path) -> Option<bool> {
+    let status = git(path, &["status", "--porcelain"]).await.ok()?;
+    Some(status.trim().is_empty())
+}
+
+/// Removes the worktree at `path`. Git refuses when it has changes, so
+/// nothing uncommitted is lost.
+pub async fn remove(path: &Path) -> Result<(), String> {
+    let repo = main_root(path).await;
+    git(&repo, &["worktree", "remove", &path.to_string_lossy()])
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_worktrees_by_repo_and_branch() {
+        let repo = Path::new("/code/api");
+        assert_eq!(
+            worktree_path(None, repo, "feature/login"),
+            PathBuf::from("/code/api-worktrees/feature-login")
+        );
+        assert_eq!(
+            worktree_path(Some(Path::new("/wt")), repo, "fix"),
+            PathBuf::from("/wt/api/fix")
+        );
+    }
+}